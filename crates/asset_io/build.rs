@@ -22,7 +22,8 @@ fn main() {
     visit_dirs(&dir)
         .iter()
         .filter(|path| {
-            ["png", "ttf"].contains(&path.extension().and_then(|e| e.to_str()).unwrap_or("zut"))
+            ["png", "ttf", "wav", "ogg", "mp3"]
+                .contains(&path.extension().and_then(|e| e.to_str()).unwrap_or("zut"))
         })
         .map(|path| (path, path.strip_prefix(&dir).unwrap()))
         .for_each(|(fullpath, path)| {
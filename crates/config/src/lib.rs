@@ -1,22 +1,199 @@
-use std::path::Path;
+use std::{fmt, path::Path};
 
 use hocon::HoconLoader;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-pub fn read_from<'a, T>(path: &str) -> Result<T, Box<dyn std::error::Error>>
+/// A problem found in a configuration file.
+#[derive(Debug, Clone)]
+pub struct Error {
+    pub file: String,
+    pub key: Option<String>,
+    pub expected: Option<&'static str>,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Error {
+    fn new(file: &Path, message: impl ToString) -> Self {
+        Error {
+            file: file.display().to_string(),
+            key: None,
+            expected: None,
+            line: None,
+            message: message.to_string(),
+        }
+    }
+
+    /// A problem with the value of `key`, with the line where it is set if it can be found.
+    pub fn for_key(file: impl AsRef<Path>, key: &str, message: impl ToString) -> Self {
+        let file = file.as_ref();
+        Error {
+            key: Some(key.to_string()),
+            line: find_line(file, key),
+            ..Error::new(file, message)
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file)?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if let Some(key) = self.key.as_ref() {
+            write!(f, ": `{}`", key)?;
+        }
+        if let Some(expected) = self.expected {
+            write!(f, ": expected {}", expected)?;
+            if !self.message.is_empty() {
+                write!(f, ",")?;
+            }
+        } else {
+            write!(f, ":")?;
+        }
+        if !self.message.is_empty() {
+            write!(f, " {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// All the problems found while reading or validating configuration files.
+#[derive(Debug, Default)]
+pub struct Errors(pub Vec<Error>);
+
+impl fmt::Display for Errors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Errors {}
+
+impl From<Error> for Errors {
+    fn from(error: Error) -> Self {
+        Errors(vec![error])
+    }
+}
+
+pub fn read_from<'a, T>(path: impl AsRef<Path>) -> Result<T, Errors>
 where
     T: Default + Serialize + Deserialize<'a>,
 {
     let default = T::default();
+    let path = path.as_ref();
+
+    if path.exists() {
+        let default_as_json =
+            serde_json::to_string(&default).map_err(|err| Error::new(path, err))?;
+
+        let from_file: Value = HoconLoader::new()
+            .load_file(&*path.to_string_lossy())
+            .and_then(|loader| loader.resolve())
+            .map_err(|err| Error::new(path, err))?;
+        let default_as_value: Value =
+            serde_json::from_str(&default_as_json).map_err(|err| Error::new(path, err))?;
+        let mut errors = vec![];
+        check_types(path, &default_as_value, &from_file, "", &mut errors);
+        if !errors.is_empty() {
+            errors.sort_by_key(|error| error.line);
+            return Err(Errors(errors));
+        }
 
-    if Path::new(path).exists() {
-        let default_as_json = serde_json::to_string(&default)?;
         let parsed: T = HoconLoader::new()
-            .load_str(&default_as_json)?
-            .load_file(path)?
-            .resolve()?;
+            .load_str(&default_as_json)
+            .and_then(|loader| loader.load_file(&*path.to_string_lossy()))
+            .and_then(|loader| loader.resolve())
+            .map_err(|err| Error::new(path, err))?;
         Ok(parsed)
     } else {
         Ok(default)
     }
 }
+
+pub fn write_to<T>(path: impl AsRef<Path>, value: &T) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: Serialize,
+{
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+fn expected_type(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some("a boolean"),
+        Value::Number(n) if n.is_u64() => Some("a positive integer"),
+        Value::Number(n) if n.is_i64() => Some("an integer"),
+        Value::Number(_) => Some("a number"),
+        Value::String(_) => Some("a string"),
+        Value::Array(_) => Some("a list"),
+        Value::Object(_) => Some("an object"),
+    }
+}
+
+fn matches_type(default: &Value, value: &Value) -> bool {
+    match (default, value) {
+        (Value::Null, _) => true,
+        (Value::Bool(_), Value::Bool(_)) => true,
+        (Value::Number(d), Value::Number(v)) if d.is_u64() => v.is_u64(),
+        (Value::Number(d), Value::Number(v)) if d.is_i64() => v.is_i64() || v.is_u64(),
+        (Value::Number(_), Value::Number(_)) => true,
+        (Value::String(_), Value::String(_)) => true,
+        (Value::Array(_), Value::Array(_)) => true,
+        (Value::Object(_), Value::Object(_)) => true,
+        _ => false,
+    }
+}
+
+fn check_types(path: &Path, default: &Value, value: &Value, prefix: &str, errors: &mut Vec<Error>) {
+    if let (Value::Object(default), Value::Object(value)) = (default, value) {
+        for (key, value) in value {
+            let full_key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match default.get(key) {
+                None => errors.push(Error::for_key(path, &full_key, "unknown key")),
+                Some(default) if !matches_type(default, value) => errors.push(Error {
+                    expected: expected_type(default),
+                    ..Error::for_key(path, &full_key, format!("found {}", value))
+                }),
+                Some(default) => check_types(path, default, value, &full_key, errors),
+            }
+        }
+    }
+}
+
+fn find_line(path: &Path, key: &str) -> Option<usize> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let last = key.rsplit('.').next().unwrap_or(key);
+    content
+        .lines()
+        .position(|line| {
+            let line = line.trim_start().trim_start_matches('"');
+            [key, last].iter().any(|key| {
+                line.starts_with(key)
+                    && line[key.len()..]
+                        .trim_start_matches('"')
+                        .trim_start()
+                        .starts_with(|c| c == '=' || c == ':' || c == '{')
+            })
+        })
+        .map(|line| line + 1)
+}
@@ -0,0 +1,263 @@
+use std::{error::Error, fmt, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+/// A crate placed on the board before the game starts.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArenaCrate {
+    pub x: usize,
+    pub y: usize,
+    pub strength: usize,
+}
+
+/// A hand designed board, with its walls, crates and where players start.
+///
+/// Arenas are written as text, with `//` comments, `key = value` lines and the board drawn from
+/// its top row to its bottom row:
+///
+/// ```text
+/// // one character per cell: `.` empty, `#` wall, `1` to `9` crate of that strength,
+/// // `A` and `B` where players one and two start
+/// name = Corridors
+/// .........
+/// ..3...#..
+/// |..A...#..|
+/// .....2...
+/// ```
+///
+/// A row between `|` is a blocked lane: players can't teleport from one side to the other on it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Arena {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub walls: Vec<(usize, usize)>,
+    pub crates: Vec<ArenaCrate>,
+    /// Where each player starts, in player order
+    pub starts: Vec<(usize, usize)>,
+    /// Rows where players can't teleport from one side of the board to the other
+    pub blocked_lanes: Vec<usize>,
+}
+
+const PLAYER_STARTS: [char; 2] = ['A', 'B'];
+
+/// Walls of the board when the game is not played on an arena.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BoardLayout {
+    /// No walls
+    #[default]
+    Empty,
+    /// A wall every other cell, on every other row
+    Pillars,
+    /// A wall every four cells, shifted from one row of walls to the next
+    SparsePillars,
+}
+
+impl BoardLayout {
+    /// Arena with the walls of this layout. The edges of the board and where players start are
+    /// kept free.
+    pub fn arena(self, width: usize, height: usize) -> Arena {
+        let mut arena = Arena::empty(width, height);
+        let step = match self {
+            BoardLayout::Empty => return arena,
+            BoardLayout::Pillars => 2,
+            BoardLayout::SparsePillars => 4,
+        };
+        let starts = (1..=PLAYER_STARTS.len())
+            .flat_map(|nb_players| {
+                let arena = &arena;
+                (0..nb_players).map(move |player| arena.start(player, nb_players))
+            })
+            .collect::<Vec<_>>();
+        for (row, y) in (1..height.saturating_sub(1)).step_by(2).enumerate() {
+            let shift = if self == BoardLayout::SparsePillars && row % 2 == 1 {
+                step / 2
+            } else {
+                0
+            };
+            for x in (2 + shift..width.saturating_sub(2)).step_by(step) {
+                if !starts.contains(&(x, y)) {
+                    arena.walls.push((x, y));
+                }
+            }
+        }
+        arena
+    }
+}
+
+impl Arena {
+    /// An arena without anything on it, where players start at the usual place.
+    pub fn empty(width: usize, height: usize) -> Self {
+        Arena {
+            name: String::new(),
+            width,
+            height,
+            walls: vec![],
+            crates: vec![],
+            starts: vec![],
+            blocked_lanes: vec![],
+        }
+    }
+
+    /// Where `player` starts in a game with `nb_players`, at the usual place when the arena
+    /// doesn't say. When a wall, a crate or another player is already there, the closest free
+    /// cell is used instead.
+    pub fn start(&self, player: usize, nb_players: usize) -> (usize, usize) {
+        if let Some(start) = self.starts.get(player) {
+            return *start;
+        }
+        let (width, height) = (self.width, self.height);
+        let usual = match (nb_players, player) {
+            (1, _) => (width / 4, height / 2),
+            (_, 0) => (width / 4, height - 1 - height / 3),
+            _ => (width / 4, height / 3),
+        };
+        let taken = (0..player)
+            .map(|other| self.start(other, nb_players))
+            .collect::<Vec<_>>();
+        let distance = |(x, y): (usize, usize)| {
+            (x as isize - usual.0 as isize).abs() + (y as isize - usual.1 as isize).abs()
+        };
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|cell| self.is_free(*cell) && !taken.contains(cell))
+            .min_by_key(|cell| distance(*cell))
+            .unwrap_or(usual)
+    }
+
+    fn is_free(&self, (x, y): (usize, usize)) -> bool {
+        !self.walls.contains(&(x, y))
+            && !self.crates.iter().any(|c| (c.x, c.y) == (x, y))
+            && !self.starts.contains(&(x, y))
+    }
+
+    pub fn parse(text: &str) -> Result<Arena, String> {
+        let mut name = String::new();
+        let mut rows = vec![];
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            if let Some(separator) = line.find('=') {
+                let (key, value) = (line[..separator].trim(), line[separator + 1..].trim());
+                match key {
+                    "name" => name = value.to_string(),
+                    _ => return Err(format!("line {}: unknown key `{}`", i + 1, key)),
+                }
+                continue;
+            }
+            let blocked = line.starts_with('|') || line.ends_with('|');
+            rows.push((i + 1, line.trim_matches('|'), blocked));
+        }
+
+        let height = rows.len();
+        let width = rows
+            .first()
+            .map(|(_, row, _)| row.chars().count())
+            .unwrap_or(0);
+        if width < 4 || height < 1 {
+            return Err(format!(
+                "the board must be at least 4 wide and 1 high, found {}x{}",
+                width, height
+            ));
+        }
+        let mut arena = Arena {
+            name,
+            ..Arena::empty(width, height)
+        };
+        let mut starts = vec![None; PLAYER_STARTS.len()];
+        // the first row is the top of the board
+        for (row_index, (line, row, blocked)) in rows.into_iter().enumerate() {
+            let y = height - 1 - row_index;
+            if row.chars().count() != width {
+                return Err(format!(
+                    "line {}: expected {} cells, found {}",
+                    line,
+                    width,
+                    row.chars().count()
+                ));
+            }
+            if blocked {
+                arena.blocked_lanes.push(y);
+            }
+            for (x, cell) in row.chars().enumerate() {
+                match cell {
+                    '.' => (),
+                    '#' => arena.walls.push((x, y)),
+                    '1'..='9' => arena.crates.push(ArenaCrate {
+                        x,
+                        y,
+                        strength: cell.to_digit(10).unwrap() as usize,
+                    }),
+                    _ => match PLAYER_STARTS.iter().position(|start| *start == cell) {
+                        Some(player) if starts[player].is_some() => {
+                            return Err(format!(
+                                "line {}: player {} already starts somewhere else",
+                                line, cell
+                            ))
+                        }
+                        Some(player) => starts[player] = Some((x, y)),
+                        None => return Err(format!("line {}: unknown cell `{}`", line, cell)),
+                    },
+                }
+            }
+        }
+        arena.blocked_lanes.sort_unstable();
+        if starts[0].is_none() && starts[1].is_some() {
+            return Err("player B starts somewhere but not player A".to_string());
+        }
+        arena.starts = starts.into_iter().flatten().collect();
+        Ok(arena)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Arena, Box<dyn Error>> {
+        let path = path.as_ref();
+        Arena::parse(&fs::read_to_string(path)?)
+            .map_err(|err| format!("{}: {}", path.display(), err).into())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    fn cell(&self, x: usize, y: usize) -> char {
+        if let Some(player) = self.starts.iter().position(|start| *start == (x, y)) {
+            PLAYER_STARTS[player]
+        } else if self.walls.contains(&(x, y)) {
+            '#'
+        } else if let Some(obstacle) = self.crates.iter().find(|c| (c.x, c.y) == (x, y)) {
+            std::char::from_digit(obstacle.strength.clamp(1, 9) as u32, 10).unwrap()
+        } else {
+            '.'
+        }
+    }
+}
+
+impl fmt::Display for Arena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "// one character per cell: `.` empty, `#` wall, `1` to `9` crate of that strength,"
+        )?;
+        writeln!(f, "// `A` and `B` where players one and two start")?;
+        writeln!(
+            f,
+            "// players can't teleport from one side to the other on rows between `|`"
+        )?;
+        if !self.name.is_empty() {
+            writeln!(f, "name = {}", self.name)?;
+        }
+        for y in (0..self.height).rev() {
+            let row = (0..self.width).map(|x| self.cell(x, y)).collect::<String>();
+            if self.blocked_lanes.contains(&y) {
+                writeln!(f, "|{}|", row)?;
+            } else {
+                writeln!(f, "{}", row)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+//! Play games without a window, to see how a config plays out before anyone plays it.
+//!
+//! ```text
+//! kmanb-sim [--games 100] [--config config.conf] [--seed 42] [--mode solo|versus|coop]
+//!           [--arena arena.txt] [--replay replay.json [--override-config]]
+//!           [--format csv|json|text] [--balance]
+//! ```
+//!
+//! Games are played by bots, or by the actions of a replay with the config it was recorded with.
+//! With `--override-config`, the replay is played against the given config instead, without the
+//! config changes made during the game. One line is
+//! printed per player and game, followed by a summary of each value: as more CSV blocks separated
+//! by an empty line with `csv`, or only the summary in sentences with `text`. With `--balance`,
+//! only the average round reached in solo games is printed.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use simulation::{
+    average_round, cli::arg_value, play_with_bots, Arena, Config, GameStats, Mode, Replay,
+    Simulation,
+};
+
+#[derive(Serialize)]
+struct Row {
+    game: usize,
+    seed: u64,
+    player: usize,
+    score: u32,
+    total_score: u32,
+    round: u16,
+    time: u64,
+    cause_of_death: Option<String>,
+    bomb_placed: u16,
+    bomb_chained: u16,
+    obstacle_destroyed: u16,
+    score_first_bomb: u32,
+    revived: u16,
+    obstacle_destroyed_by_laser: u16,
+    obstacle_destroyed_by_barrel: u16,
+    crate_destroyed: u16,
+    barrel_destroyed: u16,
+    metal_destroyed: u16,
+    regenerating_destroyed: u16,
+    treasure_destroyed: u16,
+}
+
+impl Row {
+    fn new(game: usize, sim: &Simulation, player: usize, game_stats: &GameStats) -> Self {
+        let stats = game_stats.of(player);
+        Row {
+            game,
+            seed: sim.seed(),
+            player,
+            score: sim.players()[player].score,
+            total_score: sim.score(),
+            round: sim.round(),
+            time: sim.time(),
+            cause_of_death: stats.cause_of_death.map(|cause| format!("{:?}", cause)),
+            bomb_placed: stats.bomb_placed,
+            bomb_chained: stats.bomb_chained,
+            obstacle_destroyed: stats.obstacle_destroyed,
+            score_first_bomb: stats.score_first_bomb,
+            revived: stats.revived,
+            obstacle_destroyed_by_laser: game_stats.obstacle_destroyed_by_laser,
+            obstacle_destroyed_by_barrel: game_stats.obstacle_destroyed_by_barrel,
+            crate_destroyed: game_stats.crate_destroyed,
+            barrel_destroyed: game_stats.barrel_destroyed,
+            metal_destroyed: game_stats.metal_destroyed,
+            regenerating_destroyed: game_stats.regenerating_destroyed,
+            treasure_destroyed: game_stats.treasure_destroyed,
+        }
+    }
+
+    /// Numeric values, by name, in the order of the CSV columns.
+    fn values(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("score", self.score as f64),
+            ("total_score", self.total_score as f64),
+            ("round", self.round as f64),
+            ("time", self.time as f64),
+            ("bomb_placed", self.bomb_placed as f64),
+            ("bomb_chained", self.bomb_chained as f64),
+            ("obstacle_destroyed", self.obstacle_destroyed as f64),
+            ("score_first_bomb", self.score_first_bomb as f64),
+            ("revived", self.revived as f64),
+            (
+                "obstacle_destroyed_by_laser",
+                self.obstacle_destroyed_by_laser as f64,
+            ),
+            (
+                "obstacle_destroyed_by_barrel",
+                self.obstacle_destroyed_by_barrel as f64,
+            ),
+            ("crate_destroyed", self.crate_destroyed as f64),
+            ("barrel_destroyed", self.barrel_destroyed as f64),
+            ("metal_destroyed", self.metal_destroyed as f64),
+            ("regenerating_destroyed", self.regenerating_destroyed as f64),
+            ("treasure_destroyed", self.treasure_destroyed as f64),
+        ]
+    }
+}
+
+#[derive(Serialize)]
+struct Distribution {
+    min: f64,
+    median: f64,
+    mean: f64,
+    max: f64,
+}
+
+impl Distribution {
+    fn of(mut values: Vec<f64>) -> Self {
+        if values.is_empty() {
+            return Distribution {
+                min: 0.,
+                median: 0.,
+                mean: 0.,
+                max: 0.,
+            };
+        }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Distribution {
+            min: values[0],
+            median: values[values.len() / 2],
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            max: values[values.len() - 1],
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Summary {
+    games: usize,
+    values: BTreeMap<&'static str, Distribution>,
+    cause_of_death: BTreeMap<String, usize>,
+}
+
+impl Summary {
+    fn new(games: usize, rows: &[Row]) -> Self {
+        let mut values = BTreeMap::new();
+        if let Some(first) = rows.first() {
+            for (i, (name, _)) in first.values().iter().enumerate() {
+                let column = rows.iter().map(|row| row.values()[i].1).collect();
+                values.insert(*name, Distribution::of(column));
+            }
+        }
+        let mut cause_of_death = BTreeMap::new();
+        for row in rows {
+            let cause = row
+                .cause_of_death
+                .clone()
+                .unwrap_or_else(|| "survived".to_string());
+            *cause_of_death.entry(cause).or_insert(0) += 1;
+        }
+        Summary {
+            games,
+            values,
+            cause_of_death,
+        }
+    }
+}
+
+enum Format {
+    Csv,
+    Json,
+    Text,
+}
+
+fn parse_mode(mode: &str) -> Result<Mode, String> {
+    match mode {
+        "solo" => Ok(Mode::Solo),
+        "versus" => Ok(Mode::Versus),
+        "coop" => Ok(Mode::Coop),
+        _ => Err(format!(
+            "unknown mode {}, expected solo, versus or coop",
+            mode
+        )),
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config_file = arg_value("--config").unwrap_or_else(|| "config.conf".to_string());
+    let config = match Config::load(&config_file) {
+        Ok(config) => config,
+        Err(errors) => {
+            eprintln!("{}", errors);
+            std::process::exit(1);
+        }
+    };
+    let nb_games: usize = match arg_value("--games") {
+        Some(games) => games.parse()?,
+        None => 100,
+    };
+    let seed: u64 = match arg_value("--seed") {
+        Some(seed) => seed.parse()?,
+        None => rand::random(),
+    };
+    if std::env::args().any(|arg| arg == "--balance") {
+        println!(
+            "average round reached by the bot over {} games: {:.1}",
+            nb_games,
+            average_round(&config, nb_games, seed)
+        );
+        return Ok(());
+    }
+    let mode = arg_value("--mode")
+        .map(|mode| parse_mode(&mode))
+        .transpose()?
+        .unwrap_or_default();
+    let format = match arg_value("--format").as_deref() {
+        None | Some("csv") => Format::Csv,
+        Some("json") => Format::Json,
+        Some("text") => Format::Text,
+        Some(format) => {
+            return Err(format!("unknown format {}, expected csv, json or text", format).into())
+        }
+    };
+    let arena = arg_value("--arena").map(Arena::load).transpose()?;
+    let replay = arg_value("--replay").map(Replay::load).transpose()?;
+    let override_config = std::env::args().any(|arg| arg == "--override-config");
+
+    let games = match replay {
+        // a replay always plays out the same, only play it once
+        Some(replay) if override_config => vec![Simulation::from_replay(&Replay {
+            config: config.clone(),
+            config_changes: vec![],
+            ..replay
+        })],
+        Some(replay) => vec![Simulation::from_replay(&replay)],
+        None => (0..nb_games as u64)
+            .map(|game| {
+                let seed = seed.wrapping_add(game);
+                match arena.clone() {
+                    Some(arena) => Simulation::with_arena(config.clone(), seed, mode, arena),
+                    None => Simulation::with_mode(config.clone(), seed, mode),
+                }
+            })
+            .collect(),
+    };
+
+    let mut rows = vec![];
+    let nb_games = games.len();
+    for (game, mut sim) in games.into_iter().enumerate() {
+        let stats = play_with_bots(&mut sim);
+        for player in 0..sim.players().len() {
+            rows.push(Row::new(game, &sim, player, &stats));
+        }
+    }
+    let summary = Summary::new(nb_games, &rows);
+
+    match format {
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "games": rows,
+                "summary": summary,
+            }))?
+        ),
+        Format::Csv => print_csv(&rows, &summary),
+        Format::Text => print_text(&summary),
+    }
+    Ok(())
+}
+
+fn print_csv(rows: &[Row], summary: &Summary) {
+    let mut header = vec!["game", "seed", "player", "cause_of_death"];
+    if let Some(first) = rows.first() {
+        header.extend(first.values().iter().map(|(name, _)| *name));
+    }
+    println!("{}", header.join(","));
+    for row in rows {
+        let mut line = vec![
+            row.game.to_string(),
+            row.seed.to_string(),
+            row.player.to_string(),
+            row.cause_of_death.clone().unwrap_or_default(),
+        ];
+        line.extend(row.values().iter().map(|(_, value)| value.to_string()));
+        println!("{}", line.join(","));
+    }
+
+    println!();
+    println!("value,min,median,mean,max");
+    for (name, distribution) in &summary.values {
+        println!(
+            "{},{},{},{:.1},{}",
+            name, distribution.min, distribution.median, distribution.mean, distribution.max
+        );
+    }
+
+    println!();
+    println!("cause_of_death,count");
+    for (cause, count) in &summary.cause_of_death {
+        println!("{},{}", cause, count);
+    }
+}
+
+fn print_text(summary: &Summary) {
+    println!("{} games", summary.games);
+    for (name, distribution) in &summary.values {
+        println!(
+            "{}: min {}, median {}, mean {:.1}, max {}",
+            name, distribution.min, distribution.median, distribution.mean, distribution.max
+        );
+    }
+    for (cause, count) in &summary.cause_of_death {
+        println!("{}: {}", cause, count);
+    }
+}
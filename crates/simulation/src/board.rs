@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Arena, MoveDirection, PlayerPowerUp, Timer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BombState {
+    Fuse,
+    Flash,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bomb {
+    pub damage: usize,
+    pub range: usize,
+    pub state: BombState,
+    pub timer: Timer,
+    pub owner: usize,
+    /// Fire goes through obstacles
+    pub pierce: bool,
+    /// Waits for its owner to set it off
+    pub remote: bool,
+    /// Direction the bomb slides to after being kicked
+    pub(crate) sliding: Option<(MoveDirection, Timer)>,
+}
+
+/// What set a cell on fire
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FireSource {
+    /// The trail of the laser
+    Laser,
+    /// A bomb of this player
+    Bomb(usize),
+    /// A barrel destroyed by this player, or by the laser when `None`
+    Barrel(Option<usize>),
+}
+
+impl FireSource {
+    /// Player who started the fire, with a bomb or by destroying a barrel
+    pub fn player(self) -> Option<usize> {
+        match self {
+            FireSource::Laser => None,
+            FireSource::Bomb(player) => Some(player),
+            FireSource::Barrel(player) => player,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Fire {
+    pub damage: usize,
+    pub timer: Timer,
+    pub source: FireSource,
+}
+
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, enum_utils::IterVariants,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ObstacleKind {
+    Crate,
+    /// Explodes like a bomb when destroyed
+    Barrel,
+    /// Not damaged by the laser fire
+    Metal,
+    /// Gets its life back over time
+    Regenerating,
+    /// Always drops a power up
+    Treasure,
+}
+
+#[derive(Debug, Clone)]
+pub struct Obstacle {
+    pub kind: ObstacleKind,
+    pub original_life: i32,
+    pub remaining_life: i32,
+    /// Milliseconds since a regenerating obstacle last got some life back
+    pub(crate) regeneration: u64,
+}
+
+impl Obstacle {
+    pub fn new(life: usize) -> Self {
+        Obstacle::with_kind(ObstacleKind::Crate, life)
+    }
+
+    pub fn with_kind(kind: ObstacleKind, life: usize) -> Self {
+        Obstacle {
+            kind,
+            original_life: life as i32,
+            remaining_life: life as i32,
+            regeneration: 0,
+        }
+    }
+
+    /// Remaining life, as a ratio of the original life.
+    pub fn state(&self) -> f32 {
+        self.remaining_life as f32 / self.original_life as f32
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PowerUp {
+    pub powerup: PlayerPowerUp,
+    pub timer: Timer,
+    pub used: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Cell {
+    pub obstacle: Option<Obstacle>,
+    pub bomb: Option<Bomb>,
+    pub fire: Option<Fire>,
+    pub powerup: Option<PowerUp>,
+    /// Walls can't be destroyed, walked through or burned
+    pub wall: bool,
+}
+
+impl Cell {
+    /// A cell is occupied when nothing else can be spawned on it.
+    pub fn is_occupied(&self) -> bool {
+        self.wall || self.obstacle.is_some() || self.bomb.is_some() || self.powerup.is_some()
+    }
+
+    /// A cell is blocking when players can't walk on it.
+    pub fn is_blocking(&self) -> bool {
+        self.wall || self.obstacle.is_some()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Board {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    blocked_lanes: Vec<usize>,
+}
+
+impl Board {
+    pub fn new(width: usize, height: usize) -> Self {
+        Board {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+            blocked_lanes: vec![],
+        }
+    }
+
+    pub fn from_arena(arena: &Arena) -> Self {
+        let mut board = Board::new(arena.width, arena.height);
+        for (x, y) in &arena.walls {
+            board.cell_mut(*x, *y).wall = true;
+        }
+        for obstacle in &arena.crates {
+            board.cell_mut(obstacle.x, obstacle.y).obstacle =
+                Some(Obstacle::new(obstacle.strength));
+        }
+        board.blocked_lanes = arena.blocked_lanes.clone();
+        board
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Players can teleport from one side of the board to the other, unless the lane is blocked.
+    pub fn can_teleport(&self, y: usize) -> bool {
+        !self.blocked_lanes.contains(&y)
+    }
+
+    pub fn cell(&self, x: usize, y: usize) -> &Cell {
+        &self.cells[y * self.width + x]
+    }
+
+    pub(crate) fn cell_mut(&mut self, x: usize, y: usize) -> &mut Cell {
+        &mut self.cells[y * self.width + x]
+    }
+
+    /// Iterate over all cells with their coordinates.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, &Cell)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (i % width, i / width, cell))
+    }
+
+    pub(crate) fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i % width, i / width))
+    }
+}
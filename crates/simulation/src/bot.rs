@@ -0,0 +1,309 @@
+use std::collections::VecDeque;
+
+use crate::{Board, BombState, Config, GameStats, MoveDirection, PlayerAction, Simulation};
+
+const DIRECTIONS: [MoveDirection; 4] = [
+    MoveDirection::Up,
+    MoveDirection::Down,
+    MoveDirection::Left,
+    MoveDirection::Right,
+];
+
+/// Number of laser moves ahead after which a column is considered as safe as any other.
+const LASER_HORIZON: usize = 8;
+
+/// Games played by bots are stopped after this much simulated time, in milliseconds.
+const MAX_GAME_TIME: u64 = 30 * 60 * 1000;
+
+/// A computer controlled player, choosing where to go by reading the board.
+#[derive(Debug, Clone, Copy)]
+pub struct Bot {
+    player: usize,
+}
+
+/// What is dangerous on the board right now.
+struct Danger {
+    width: usize,
+    height: usize,
+    /// for each column, number of laser moves before it is set on fire
+    laser: Vec<usize>,
+    /// the column the laser kills right now
+    laser_kills: Option<usize>,
+    fire: Vec<bool>,
+    /// for each cell, milliseconds before a bomb explodes on it
+    explosion: Vec<Option<u64>>,
+}
+
+impl Danger {
+    fn new(sim: &Simulation) -> Self {
+        let board = sim.board();
+        let (width, height) = (board.width(), board.height());
+        let laser_x = sim.laser().x;
+        // the laser sets column `c` on fire when moving from `c + 1`, and wraps after `width`
+        let laser = (0..width)
+            .map(|column| (column + 1 + (width + 1) - laser_x) % (width + 1))
+            .collect();
+        let mut danger = Danger {
+            width,
+            height,
+            laser,
+            laser_kills: laser_x.checked_sub(2),
+            fire: vec![false; width * height],
+            explosion: vec![None; width * height],
+        };
+        for (x, y, cell) in board.cells() {
+            if cell.fire.is_some() {
+                danger.fire[y * width + x] = true;
+            }
+            if let Some(bomb) = cell.bomb.as_ref() {
+                let explodes_in = match bomb.state {
+                    BombState::Fuse => bomb.timer.remaining() + bomb.timer.duration,
+                    BombState::Flash => bomb.timer.remaining(),
+                };
+                // bombs waiting for a remote are expected to go off as if they were not
+                for (bx, by) in blast(board, x, y, bomb.range, bomb.pierce) {
+                    let explosion = &mut danger.explosion[by * width + bx];
+                    *explosion = Some(explosion.map_or(explodes_in, |t| t.min(explodes_in)));
+                }
+            }
+        }
+        danger
+    }
+
+    /// Can a player go through `(x, y)`, arriving in `arrival` milliseconds and staying there
+    /// for `stay` milliseconds
+    fn can_walk(&self, x: usize, y: usize, arrival: u64, stay: u64) -> bool {
+        let i = y * self.width + x;
+        !self.fire[i]
+            && self.laser[x] > 0
+            && self.laser_kills != Some(x)
+            && self.explosion[i]
+                .map(|explosion| explosion > arrival + stay)
+                .unwrap_or(true)
+    }
+
+    fn is_safe(&self, x: usize, y: usize) -> bool {
+        self.can_walk(x, y, 0, 0)
+            && self.explosion[y * self.width + x].is_none()
+            && self.laser[x] > 1
+    }
+}
+
+/// Cells that would be set on fire by a bomb at `(x, y)`, following how bombs explode.
+fn blast(board: &Board, x: usize, y: usize, range: usize, pierce: bool) -> Vec<(usize, usize)> {
+    let (width, height) = (board.width(), board.height());
+    let mut cells = vec![];
+    let mut burn = |x: usize, y: usize| {
+        let cell = board.cell(x, y);
+        if !cell.wall {
+            cells.push((x, y));
+        }
+        cell.wall || (cell.is_blocking() && !pierce)
+    };
+    let (mut stop_right, mut stop_left, mut stop_top, mut stop_bottom) =
+        (false, false, false, false);
+    for i in 0..=range {
+        if !stop_right && x + i < width {
+            stop_right = burn(x + i, y);
+        }
+        if !stop_left && x as i32 - i as i32 > 0 {
+            stop_left = burn(x - i, y);
+        }
+        if !stop_top && y + i < height {
+            stop_top = burn(x, y + i);
+        }
+        if !stop_bottom && y as i32 - i as i32 >= 0 {
+            stop_bottom = burn(x, y - i);
+        }
+    }
+    cells
+}
+
+/// Where a move from `(x, y)` ends, following how players move and teleport on the edges.
+fn destination(
+    board: &Board,
+    x: usize,
+    y: usize,
+    direction: MoveDirection,
+) -> Option<(usize, usize)> {
+    let (width, height) = (board.width() as i32, board.height() as i32);
+    let change = direction.change();
+    let (x, y) = (x as i32 + change.0, y as i32 + change.1);
+    if y < 0 || y >= height {
+        None
+    } else if x < 0 || x >= width {
+        let x = if x < 0 { width - 1 } else { 0 };
+        if !board.can_teleport(y as usize) || board.cell(x as usize, y as usize).is_blocking() {
+            None
+        } else {
+            Some((x as usize, y as usize))
+        }
+    } else if board.cell(x as usize, y as usize).is_blocking() {
+        None
+    } else {
+        Some((x as usize, y as usize))
+    }
+}
+
+/// A cell that can be reached, with the first move to get there.
+#[derive(Debug, Clone, Copy)]
+struct Path {
+    x: usize,
+    y: usize,
+    distance: usize,
+    first_move: Option<MoveDirection>,
+}
+
+fn paths(board: &Board, danger: &Danger, from: (usize, usize), speed: u64) -> Vec<Path> {
+    // moves can be slower than the player speed when buffered, keep a margin
+    let step = speed * 3 / 2;
+    let width = board.width();
+    let mut visited = vec![false; width * board.height()];
+    let mut paths = vec![];
+    let mut queue = VecDeque::new();
+    visited[from.1 * width + from.0] = true;
+    queue.push_back(Path {
+        x: from.0,
+        y: from.1,
+        distance: 0,
+        first_move: None,
+    });
+    while let Some(path) = queue.pop_front() {
+        for direction in DIRECTIONS.iter() {
+            if let Some((x, y)) = destination(board, path.x, path.y, *direction) {
+                let arrival = (path.distance as u64 + 1) * step;
+                if visited[y * width + x] || !danger.can_walk(x, y, arrival, step) {
+                    continue;
+                }
+                visited[y * width + x] = true;
+                queue.push_back(Path {
+                    x,
+                    y,
+                    distance: path.distance + 1,
+                    first_move: path.first_move.or(Some(*direction)),
+                });
+            }
+        }
+        paths.push(path);
+    }
+    paths
+}
+
+impl Bot {
+    pub fn new(player: usize) -> Self {
+        Bot { player }
+    }
+
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    /// What the bot wants to do now. Nothing is done while the player is still moving, so that
+    /// replays of bot games don't record actions the simulation would ignore.
+    pub fn action(&self, sim: &Simulation) -> Option<PlayerAction> {
+        let player = sim.players().get(self.player)?;
+        if !sim.is_running() || !player.is_alive() || player.movement.is_some() {
+            return None;
+        }
+        let board = sim.board();
+        let danger = Danger::new(sim);
+        if sim.has_waiting_bombs(self.player) && danger.is_safe(player.x, player.y) {
+            // out of reach of its own bombs, set them off
+            return Some(PlayerAction::Detonate);
+        }
+        let paths = paths(board, &danger, (player.x, player.y), player.speed);
+
+        let score = |path: &Path| {
+            let laser = danger.laser[path.x].min(LASER_HORIZON) as i32;
+            let powerup = board.cell(path.x, path.y).powerup.is_some() as i32;
+            // a laser move is as long as about five player moves
+            laser * 5 + powerup * 4 - path.distance as i32
+        };
+        let target = paths
+            .iter()
+            .filter(|path| danger.is_safe(path.x, path.y))
+            .max_by_key(|path| (score(path), std::cmp::Reverse(path.distance)))
+            .or_else(|| {
+                // nowhere is safe, get as far as possible from what will burn first
+                paths.iter().max_by_key(|path| {
+                    let explosion = danger.explosion[path.y * danger.width + path.x];
+                    (explosion.unwrap_or(u64::MAX), danger.laser[path.x])
+                })
+            })?;
+
+        match target.first_move {
+            Some(direction) => Some(PlayerAction::Move(direction)),
+            None if self.should_pose_bomb(sim, &danger, &paths) => Some(PlayerAction::PoseBomb),
+            None => None,
+        }
+    }
+
+    /// Pose a bomb next to a crate when there is a safe place to hide from it in time.
+    fn should_pose_bomb(&self, sim: &Simulation, danger: &Danger, paths: &[Path]) -> bool {
+        let player = &sim.players()[self.player];
+        let board = sim.board();
+        if sim.bombs_available(self.player) == 0
+            || sim.has_waiting_bombs(self.player)
+            || board.cell(player.x, player.y).bomb.is_some()
+            || danger.laser[player.x] < 3
+        {
+            return false;
+        }
+        let next_to_crate = DIRECTIONS.iter().any(|direction| {
+            let change = direction.change();
+            let (x, y) = (player.x as i32 + change.0, player.y as i32 + change.1);
+            x >= 0
+                && y >= 0
+                && (x as usize) < danger.width
+                && (y as usize) < danger.height
+                && board.cell(x as usize, y as usize).obstacle.is_some()
+        });
+        if !next_to_crate {
+            return false;
+        }
+        let blast = blast(board, player.x, player.y, player.bomb_range, player.pierce);
+        let max_distance = (player.bomb_speed / player.speed.max(1)) as usize;
+        paths.iter().any(|path| {
+            path.distance > 0
+                && path.distance < max_distance
+                && danger.is_safe(path.x, path.y)
+                && !blast.contains(&(path.x, path.y))
+        })
+    }
+}
+
+/// Play `sim` with a bot for every player until it is lost or after half an hour, returning its
+/// stats. A replay is played with its recorded actions instead.
+pub fn play_with_bots(sim: &mut Simulation) -> GameStats {
+    let bots = (0..sim.players().len()).map(Bot::new).collect::<Vec<_>>();
+    let mut stats = GameStats::default();
+    while sim.is_running() && sim.time() < MAX_GAME_TIME {
+        let actions = if sim.is_replay() {
+            vec![]
+        } else {
+            bots.iter()
+                .filter_map(|bot| bot.action(sim).map(|action| (bot.player(), action)))
+                .collect::<Vec<_>>()
+        };
+        for event in sim.tick(&actions) {
+            stats.record(&event, sim);
+        }
+    }
+    stats
+}
+
+/// Average round reached by a bot over `nb_games` solo games with `config`, to check the balance
+/// of a config without playing it.
+pub fn average_round(config: &Config, nb_games: usize, seed: u64) -> f64 {
+    if nb_games == 0 {
+        return 0.;
+    }
+    let total: u64 = (0..nb_games as u64)
+        .map(|game| {
+            let mut sim = Simulation::new(config.clone(), seed.wrapping_add(game));
+            play_with_bots(&mut sim);
+            sim.round() as u64
+        })
+        .sum();
+    total as f64 / nb_games as f64
+}
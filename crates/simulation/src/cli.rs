@@ -0,0 +1,12 @@
+//! Helpers for the command line of the game and of `kmanb-sim`.
+
+/// Value given after the argument `name`, like `42` for `--seed 42`.
+pub fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+    }
+    None
+}
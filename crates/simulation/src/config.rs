@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BoardLayout, LaserProgression, LaserSchedule, ObstacleWeights, PlayerPowerUpWeights};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub board_width: usize,
+    pub board_height: usize,
+    /// walls on the board, when not playing on an arena
+    pub board_layout: BoardLayout,
+    pub score_bronze_medal: u32,
+    pub score_silver_medal: u32,
+    pub score_gold_medal: u32,
+    pub player_nb_bombs: usize,
+    pub player_bomb_range: usize,
+    pub player_bomb_damage: usize,
+    pub player_bomb_speed: u64,
+    pub player_speed: u64,
+    pub player_powerup_chance: f64,
+    pub player_powerup_bomb_damage: usize,
+    pub player_powerup_bomb_range: usize,
+    pub player_powerup_bomb_count: usize,
+    pub player_powerup_bomb_speed: f64,
+    pub player_powerup_score: u32,
+    pub player_powerup_speed: f64,
+    pub player_powerup_weights: PlayerPowerUpWeights,
+    /// milliseconds for a kicked bomb to slide one cell
+    pub player_kick_speed: u64,
+    /// seconds a player can't be burned after their shield absorbed a hit
+    pub player_shield_timer: f32,
+    pub player_bomb_fire_timer: f32,
+    pub powerup_timer: f32,
+    pub laser_fire_timer: f32,
+    pub laser_fire_damage: usize,
+    pub laser_speed: u64,
+    pub laser_spawn_obstacles_delay: u16,
+    pub laser_nb_obstacles: usize,
+    pub laser_obstacle_strength: usize,
+    pub laser_powerup_speed: f64,
+    pub laser_powerup_obstacle_delay: f32,
+    pub laser_powerup_obstacle_strength: usize,
+    pub laser_powerup_nb_obstacles: usize,
+    pub laser_progression: LaserProgression,
+    pub laser_schedule: LaserSchedule,
+    pub obstacle_weights: ObstacleWeights,
+    pub obstacle_barrel_range: usize,
+    pub obstacle_barrel_damage: usize,
+    /// seconds for a regenerating obstacle to get one life back
+    pub obstacle_regeneration_delay: f32,
+    pub coop_revive_time: f32,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            board_width: 21,
+            board_height: 10,
+            board_layout: BoardLayout::Empty,
+            score_bronze_medal: 10000,
+            score_silver_medal: 20000,
+            score_gold_medal: 35000,
+            player_nb_bombs: 2,
+            player_bomb_range: 1,
+            player_bomb_damage: 2,
+            player_bomb_speed: 2000,
+            player_speed: 200,
+            player_powerup_chance: 0.2,
+            player_powerup_bomb_damage: 2,
+            player_powerup_bomb_range: 1,
+            player_powerup_bomb_count: 1,
+            player_powerup_bomb_speed: 0.9,
+            player_powerup_score: 200,
+            player_powerup_speed: 0.9,
+            player_powerup_weights: PlayerPowerUpWeights::default(),
+            player_kick_speed: 60,
+            player_shield_timer: 1.5,
+            player_bomb_fire_timer: 0.25,
+            powerup_timer: 20.,
+            laser_fire_timer: 1.5,
+            laser_fire_damage: 1,
+            laser_speed: 1000,
+            laser_spawn_obstacles_delay: 10000,
+            laser_nb_obstacles: 5,
+            laser_obstacle_strength: 2,
+            laser_powerup_speed: 0.9,
+            laser_powerup_obstacle_delay: 0.8,
+            laser_powerup_obstacle_strength: 2,
+            laser_powerup_nb_obstacles: 2,
+            laser_progression: LaserProgression::Random,
+            laser_schedule: LaserSchedule::default(),
+            obstacle_weights: ObstacleWeights::default(),
+            obstacle_barrel_range: 1,
+            obstacle_barrel_damage: 2,
+            obstacle_regeneration_delay: 2.,
+            coop_revive_time: 3.,
+        }
+    }
+}
+
+impl Config {
+    /// Check that values make sense together, returning the key and the problem for each one that
+    /// doesn't.
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut problems = vec![];
+        if self.board_width < 4 {
+            problems.push(("board_width", "must be at least 4".to_string()));
+        }
+        if self.board_height < 1 {
+            problems.push(("board_height", "must be at least 1".to_string()));
+        }
+        if !(0.0..=1.0).contains(&self.player_powerup_chance) {
+            problems.push((
+                "player_powerup_chance",
+                "must be between 0 and 1".to_string(),
+            ));
+        }
+        if self.score_silver_medal <= self.score_bronze_medal {
+            problems.push((
+                "score_silver_medal",
+                format!(
+                    "must be greater than score_bronze_medal ({})",
+                    self.score_bronze_medal
+                ),
+            ));
+        }
+        if self.score_gold_medal <= self.score_silver_medal {
+            problems.push((
+                "score_gold_medal",
+                format!(
+                    "must be greater than score_silver_medal ({})",
+                    self.score_silver_medal
+                ),
+            ));
+        }
+        for (key, value) in &[
+            ("player_nb_bombs", self.player_nb_bombs as u64),
+            ("player_speed", self.player_speed),
+            ("player_bomb_speed", self.player_bomb_speed),
+            ("player_kick_speed", self.player_kick_speed),
+            ("laser_speed", self.laser_speed),
+            (
+                "laser_spawn_obstacles_delay",
+                self.laser_spawn_obstacles_delay as u64,
+            ),
+        ] {
+            if *value == 0 {
+                problems.push((key, "must not be 0".to_string()));
+            }
+        }
+        for (key, value) in &[
+            ("player_powerup_bomb_speed", self.player_powerup_bomb_speed),
+            ("player_powerup_speed", self.player_powerup_speed),
+            ("laser_powerup_speed", self.laser_powerup_speed),
+            (
+                "laser_powerup_obstacle_delay",
+                self.laser_powerup_obstacle_delay as f64,
+            ),
+            (
+                "obstacle_regeneration_delay",
+                self.obstacle_regeneration_delay as f64,
+            ),
+        ] {
+            if *value <= 0. {
+                problems.push((key, "must be greater than 0".to_string()));
+            }
+        }
+        for (key, value) in &[
+            ("player_bomb_fire_timer", self.player_bomb_fire_timer),
+            ("player_shield_timer", self.player_shield_timer),
+            ("powerup_timer", self.powerup_timer),
+            ("laser_fire_timer", self.laser_fire_timer),
+            ("coop_revive_time", self.coop_revive_time),
+        ] {
+            if *value < 0. {
+                problems.push((key, "must not be negative".to_string()));
+            }
+        }
+        problems.extend(self.laser_schedule.validate());
+        problems.extend(self.obstacle_weights.validate());
+        problems.extend(self.player_powerup_weights.validate());
+        problems
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Config {
+    /// Read a config file, reporting both the values that can't be read and the ones that are
+    /// invalid.
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Config, ::config::Errors> {
+        let path = path.as_ref();
+        let config: Config = ::config::read_from(path)?;
+        let problems = config.validate();
+        if problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(::config::Errors(
+                problems
+                    .into_iter()
+                    .map(|(key, problem)| ::config::Error::for_key(path, key, problem))
+                    .collect(),
+            ))
+        }
+    }
+}
+
+pub(crate) fn seconds_to_millis(seconds: f32) -> u64 {
+    (seconds * 1000.) as u64
+}
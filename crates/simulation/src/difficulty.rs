@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Config;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Normal => write!(f, "Normal"),
+            Difficulty::Hard => write!(f, "Hard"),
+            Difficulty::Insane => write!(f, "Insane"),
+        }
+    }
+}
+
+/// Changes from the base config for a difficulty.
+struct Preset {
+    laser_speed: f64,
+    laser_nb_obstacles: i32,
+    laser_powerup_strength: f64,
+    laser_powerup_nb_obstacles: i32,
+    player_nb_bombs: i32,
+}
+
+/// Add `change` to `value` without going under `min`. A value the preset doesn't change is kept
+/// as is, so that `Config::validate` still reports it when invalid.
+fn add(value: usize, change: i32, min: usize) -> usize {
+    if change == 0 {
+        value
+    } else {
+        usize::max(min, (value as i64 + change as i64).max(0) as usize)
+    }
+}
+
+/// Make a powerup multiplier (like 0.9 for 10% faster) `strength` times stronger.
+fn strengthen(multiplier: f64, strength: f64) -> f64 {
+    f64::max(0.1, 1. - (1. - multiplier) * strength)
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Insane,
+            Difficulty::Insane => Difficulty::Easy,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Insane,
+            Difficulty::Normal => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Normal,
+            Difficulty::Insane => Difficulty::Hard,
+        }
+    }
+
+    fn preset(self) -> Preset {
+        match self {
+            Difficulty::Easy => Preset {
+                laser_speed: 1.25,
+                laser_nb_obstacles: -1,
+                laser_powerup_strength: 0.5,
+                laser_powerup_nb_obstacles: -1,
+                player_nb_bombs: 1,
+            },
+            Difficulty::Normal => Preset {
+                laser_speed: 1.,
+                laser_nb_obstacles: 0,
+                laser_powerup_strength: 1.,
+                laser_powerup_nb_obstacles: 0,
+                player_nb_bombs: 0,
+            },
+            Difficulty::Hard => Preset {
+                laser_speed: 0.85,
+                laser_nb_obstacles: 1,
+                laser_powerup_strength: 1.5,
+                laser_powerup_nb_obstacles: 1,
+                player_nb_bombs: 0,
+            },
+            Difficulty::Insane => Preset {
+                laser_speed: 0.7,
+                laser_nb_obstacles: 3,
+                laser_powerup_strength: 2.,
+                laser_powerup_nb_obstacles: 2,
+                player_nb_bombs: -1,
+            },
+        }
+    }
+
+    /// The config to play with at this difficulty, `config` being the one for `Normal`.
+    pub fn apply(self, config: &Config) -> Config {
+        let preset = self.preset();
+        Config {
+            laser_speed: u64::max(1, (config.laser_speed as f64 * preset.laser_speed) as u64),
+            laser_nb_obstacles: add(config.laser_nb_obstacles, preset.laser_nb_obstacles, 0),
+            laser_powerup_speed: strengthen(
+                config.laser_powerup_speed,
+                preset.laser_powerup_strength,
+            ),
+            laser_powerup_obstacle_delay: strengthen(
+                config.laser_powerup_obstacle_delay as f64,
+                preset.laser_powerup_strength,
+            ) as f32,
+            laser_powerup_nb_obstacles: add(
+                config.laser_powerup_nb_obstacles,
+                preset.laser_powerup_nb_obstacles,
+                0,
+            ),
+            player_nb_bombs: add(config.player_nb_bombs, preset.player_nb_bombs, 1),
+            ..config.clone()
+        }
+    }
+}
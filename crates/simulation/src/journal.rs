@@ -0,0 +1,190 @@
+use serde::Serialize;
+
+use crate::{
+    CauseOfDeath, Event, FireSource, Mode, MoveDirection, ObstacleKind, PlayerMove, PlayerPowerUp,
+    Simulation,
+};
+
+/// What happened in a game, as written in its journal.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    GameStarted {
+        seed: u64,
+        mode: Mode,
+        players: usize,
+    },
+    NewRound,
+    PlayerMoved {
+        player: usize,
+        from: (usize, usize),
+        to: (usize, usize),
+        teleport: bool,
+    },
+    BombPlaced {
+        player: usize,
+        x: usize,
+        y: usize,
+    },
+    BombExploded {
+        player: usize,
+        x: usize,
+        y: usize,
+        chained: bool,
+    },
+    BombKicked {
+        player: usize,
+        x: usize,
+        y: usize,
+        direction: MoveDirection,
+    },
+    CrateDestroyed {
+        x: usize,
+        y: usize,
+        source: FireSource,
+        kind: ObstacleKind,
+    },
+    PowerUpTaken {
+        player: usize,
+        x: usize,
+        y: usize,
+        powerup: PlayerPowerUp,
+    },
+    PlayerDied {
+        player: usize,
+        x: usize,
+        y: usize,
+        cause: CauseOfDeath,
+    },
+    PlayerRevived {
+        player: usize,
+        x: usize,
+        y: usize,
+    },
+    ShieldUsed {
+        player: usize,
+        x: usize,
+        y: usize,
+    },
+    GameOver {
+        score: u32,
+        cause: CauseOfDeath,
+    },
+}
+
+/// One line of the journal of a game.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    /// Simulated time, in milliseconds.
+    pub time: u64,
+    pub round: u16,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+impl JournalEntry {
+    fn new(sim: &Simulation, event: JournalEvent) -> Self {
+        JournalEntry {
+            time: sim.time(),
+            round: sim.round(),
+            event,
+        }
+    }
+
+    /// First entry of a journal, describing the game.
+    pub(crate) fn start(sim: &Simulation) -> Self {
+        JournalEntry::new(
+            sim,
+            JournalEvent::GameStarted {
+                seed: sim.seed(),
+                mode: sim.mode(),
+                players: sim.players().len(),
+            },
+        )
+    }
+
+    /// Entry for an event of `sim` as it happens, if it is worth keeping.
+    pub(crate) fn from_event(event: &Event, sim: &Simulation) -> Option<Self> {
+        let position = |player: usize| (sim.players()[player].x, sim.players()[player].y);
+        let event = match *event {
+            Event::NewRound => JournalEvent::NewRound,
+            Event::PlayerMoved {
+                player,
+                from,
+                movement,
+                ..
+            } => {
+                let (to, teleport) = match movement {
+                    PlayerMove::Walk(_, x, y) => ((x, y), false),
+                    PlayerMove::Teleport(x, y) => ((x, y), true),
+                    // bumping into a crate or the border is not a move
+                    PlayerMove::Bump(_) => return None,
+                };
+                JournalEvent::PlayerMoved {
+                    player,
+                    from,
+                    to,
+                    teleport,
+                }
+            }
+            Event::BombPlaced { player, x, y } => JournalEvent::BombPlaced { player, x, y },
+            Event::BombExploded {
+                player,
+                x,
+                y,
+                chained,
+            } => JournalEvent::BombExploded {
+                player,
+                x,
+                y,
+                chained,
+            },
+            Event::BombKicked {
+                player,
+                x,
+                y,
+                direction,
+            } => JournalEvent::BombKicked {
+                player,
+                x,
+                y,
+                direction,
+            },
+            Event::ObstacleDestroyed { x, y, source, kind } => {
+                JournalEvent::CrateDestroyed { x, y, source, kind }
+            }
+            Event::PlayerBonus { player, powerup } => {
+                let (x, y) = position(player);
+                JournalEvent::PowerUpTaken {
+                    player,
+                    x,
+                    y,
+                    powerup,
+                }
+            }
+            Event::PlayerDied { player, cause } => {
+                let (x, y) = position(player);
+                JournalEvent::PlayerDied {
+                    player,
+                    x,
+                    y,
+                    cause,
+                }
+            }
+            Event::PlayerRevived { player } => {
+                let (x, y) = position(player);
+                JournalEvent::PlayerRevived { player, x, y }
+            }
+            Event::ShieldUsed { player } => {
+                let (x, y) = position(player);
+                JournalEvent::ShieldUsed { player, x, y }
+            }
+            Event::Lost(cause) => JournalEvent::GameOver {
+                score: sim.score(),
+                cause,
+            },
+            Event::LaserMoved { .. } | Event::LaserBonus(_) => return None,
+        };
+        Some(JournalEntry::new(sim, event))
+    }
+}
@@ -0,0 +1,67 @@
+use crate::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, enum_utils::IterVariants)]
+pub enum LaserPowerUp {
+    Speed,
+    ObstacleSpawnDelay,
+    ObstacleSpawnCount,
+    ObstacleStrengh,
+}
+
+#[derive(Debug, Clone)]
+pub struct Laser {
+    pub x: usize,
+    pub speed: u64,
+    pub spawn_obstacles_delay: u16,
+    pub nb_obstacles: usize,
+    pub obstacle_strength: usize,
+}
+
+impl Laser {
+    pub fn new(config: &Config) -> Self {
+        Laser {
+            x: 1,
+            speed: config.laser_speed,
+            spawn_obstacles_delay: config.laser_spawn_obstacles_delay,
+            nb_obstacles: config.laser_nb_obstacles,
+            obstacle_strength: config.laser_obstacle_strength,
+        }
+    }
+
+    pub(crate) fn power_up(&mut self, powerup: LaserPowerUp, config: &Config) {
+        match powerup {
+            LaserPowerUp::Speed => {
+                self.speed = (self.speed as f64 * config.laser_powerup_speed) as u64
+            }
+            LaserPowerUp::ObstacleSpawnDelay => {
+                self.spawn_obstacles_delay =
+                    (self.spawn_obstacles_delay as f32 * config.laser_powerup_obstacle_delay) as u16
+            }
+            LaserPowerUp::ObstacleSpawnCount => {
+                self.nb_obstacles += config.laser_powerup_nb_obstacles
+            }
+            LaserPowerUp::ObstacleStrengh => {
+                self.obstacle_strength += config.laser_powerup_obstacle_strength
+            }
+        }
+    }
+
+    /// Set the laser to the values scheduled for `round`.
+    pub(crate) fn follow_schedule(&mut self, round: u16, config: &Config) {
+        let schedule = &config.laser_schedule;
+        self.speed = (schedule.speed.at(round, config.laser_speed as f64) as u64).max(1);
+        self.spawn_obstacles_delay = (schedule
+            .spawn_obstacles_delay
+            .at(round, config.laser_spawn_obstacles_delay as f64)
+            as u16)
+            .max(1);
+        self.nb_obstacles = schedule
+            .nb_obstacles
+            .at(round, config.laser_nb_obstacles as f64) as usize;
+        self.obstacle_strength = (schedule
+            .obstacle_strength
+            .at(round, config.laser_obstacle_strength as f64)
+            as usize)
+            .max(1);
+    }
+}
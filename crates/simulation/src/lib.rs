@@ -0,0 +1,1152 @@
+use std::collections::VecDeque;
+
+use rand::{prelude::*, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+
+mod arena;
+mod board;
+mod bot;
+#[cfg(feature = "cli")]
+pub mod cli;
+mod config;
+mod difficulty;
+mod journal;
+mod laser;
+mod player;
+mod replay;
+mod schedule;
+mod stats;
+mod timer;
+
+#[cfg(test)]
+mod tests;
+
+pub use arena::{Arena, ArenaCrate, BoardLayout};
+pub use board::{Board, Bomb, BombState, Cell, Fire, FireSource, Obstacle, ObstacleKind, PowerUp};
+pub use bot::{average_round, play_with_bots, Bot};
+pub use config::Config;
+pub use difficulty::Difficulty;
+pub use journal::{JournalEntry, JournalEvent};
+pub use laser::{Laser, LaserPowerUp};
+pub use player::{
+    FacingDirection, MoveDirection, Movement, Player, PlayerAction, PlayerMove, PlayerPowerUp,
+    PlayerPowerUpWeights,
+};
+pub use replay::{ConfigChange, RecordedAction, Replay, REPLAY_VERSION};
+pub use schedule::{Curve, LaserProgression, LaserSchedule, ObstacleWeights};
+pub use stats::{GameStats, PlayerStats};
+pub use timer::Timer;
+
+use config::seconds_to_millis;
+
+/// Duration of a simulation tick, in milliseconds.
+pub const TICK_MS: u64 = 5;
+
+const SCORE_INTERVAL_MS: u64 = 100;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CauseOfDeath {
+    Laser,
+    LaserFire,
+    BombFire,
+    OpponentBomb,
+}
+
+impl std::fmt::Display for CauseOfDeath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CauseOfDeath::Laser => write!(f, "laser"),
+            CauseOfDeath::LaserFire => write!(f, "laser trail"),
+            CauseOfDeath::BombFire => write!(f, "your own bomb"),
+            CauseOfDeath::OpponentBomb => write!(f, "the other player's bomb"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Mode {
+    #[default]
+    Solo,
+    /// Two players on the same board, the last one standing wins
+    Versus,
+    /// Two players on the same board sharing one score, the game ends when both are down
+    Coop,
+}
+
+impl Mode {
+    pub fn nb_players(self) -> usize {
+        match self {
+            Mode::Solo => 1,
+            Mode::Versus | Mode::Coop => 2,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum State {
+    Running,
+    Lost(CauseOfDeath),
+}
+
+/// Everything that happened during a step, in the order it happened.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    NewRound,
+    /// The game is over, with the cause of death of the first player that is down
+    Lost(CauseOfDeath),
+    PlayerDied {
+        player: usize,
+        cause: CauseOfDeath,
+    },
+    /// A downed player in a co-op game was brought back by their partner
+    PlayerRevived {
+        player: usize,
+    },
+    PlayerMoved {
+        player: usize,
+        from: (usize, usize),
+        movement: PlayerMove,
+        duration: u64,
+    },
+    PlayerBonus {
+        player: usize,
+        powerup: PlayerPowerUp,
+    },
+    LaserMoved {
+        x: usize,
+        duration: u64,
+    },
+    LaserBonus(LaserPowerUp),
+    BombPlaced {
+        player: usize,
+        x: usize,
+        y: usize,
+    },
+    BombExploded {
+        player: usize,
+        x: usize,
+        y: usize,
+        chained: bool,
+    },
+    /// The bomb at `(x, y)` was kicked and starts sliding
+    BombKicked {
+        player: usize,
+        x: usize,
+        y: usize,
+        direction: MoveDirection,
+    },
+    /// A player's shield absorbed a fire hit
+    ShieldUsed {
+        player: usize,
+    },
+    ObstacleDestroyed {
+        x: usize,
+        y: usize,
+        source: FireSource,
+        kind: ObstacleKind,
+    },
+}
+
+/// A game of kmanb, independent of any rendering.
+///
+/// Time advances in fixed ticks of [`TICK_MS`], so that a game only depends on
+/// the actions it receives and when it receives them.
+pub struct Simulation {
+    config: Config,
+    /// config at the start of the game, replays apply the changes to it
+    initial_config: Config,
+    seed: u64,
+    mode: Mode,
+    arena: Option<Arena>,
+    rng: StdRng,
+    board: Board,
+    players: Vec<Player>,
+    shared_score: u32,
+    laser: Laser,
+    round: u16,
+    state: State,
+    time: u64,
+    accumulated_us: u64,
+    pending_actions: Vec<(usize, PlayerAction)>,
+    recorded_actions: Vec<RecordedAction>,
+    scripted_actions: Option<VecDeque<RecordedAction>>,
+    config_changes: Vec<ConfigChange>,
+    scripted_config_changes: VecDeque<ConfigChange>,
+    laser_timer: Timer,
+    obstacle_timer: Timer,
+    score_timer: Timer,
+    events: Vec<Event>,
+    /// Entries noted as events happen, when the journal was started
+    journal: Option<Vec<JournalEntry>>,
+}
+
+impl Simulation {
+    /// Start a new game. Two games with the same config, seed and actions will play out the same.
+    pub fn new(config: Config, seed: u64) -> Self {
+        Simulation::with_mode(config, seed, Mode::Solo)
+    }
+
+    pub fn with_mode(config: Config, seed: u64, mode: Mode) -> Self {
+        Simulation::build(config, seed, mode, None)
+    }
+
+    /// Start a new game on a hand designed arena instead of an empty board.
+    pub fn with_arena(config: Config, seed: u64, mode: Mode, arena: Arena) -> Self {
+        Simulation::build(config, seed, mode, Some(arena))
+    }
+
+    fn build(config: Config, seed: u64, mode: Mode, arena: Option<Arena>) -> Self {
+        let layout = arena.clone().unwrap_or_else(|| {
+            config
+                .board_layout
+                .arena(config.board_width, config.board_height)
+        });
+        let board = Board::from_arena(&layout);
+        let nb_players = mode.nb_players();
+        let players = (0..nb_players)
+            .map(|player| {
+                let (x, y) = layout.start(player, nb_players);
+                Player::new(&config, x, y)
+            })
+            .collect();
+        let laser = Laser::new(&config);
+        Simulation {
+            seed,
+            mode,
+            arena,
+            rng: StdRng::seed_from_u64(seed),
+            laser_timer: Timer::from_millis(laser.speed, true),
+            obstacle_timer: Timer::from_millis(laser.spawn_obstacles_delay as u64, true),
+            score_timer: Timer::from_millis(SCORE_INTERVAL_MS, true),
+            board,
+            players,
+            shared_score: 0,
+            laser,
+            round: 0,
+            state: State::Running,
+            time: 0,
+            accumulated_us: 0,
+            pending_actions: vec![],
+            recorded_actions: vec![],
+            scripted_actions: None,
+            config_changes: vec![],
+            scripted_config_changes: VecDeque::new(),
+            events: vec![],
+            journal: None,
+            initial_config: config.clone(),
+            config,
+        }
+    }
+
+    /// Play again a recorded game. Actions given to [`step`](Simulation::step) and
+    /// [`tick`](Simulation::tick) are ignored, the recorded ones are used instead.
+    pub fn from_replay(replay: &Replay) -> Self {
+        let mut simulation = Simulation::build(
+            replay.config.clone(),
+            replay.seed,
+            replay.mode,
+            replay.arena.clone(),
+        );
+        simulation.scripted_actions = Some(replay.actions.iter().copied().collect());
+        simulation.scripted_config_changes = replay.config_changes.iter().cloned().collect();
+        simulation
+    }
+
+    /// Record of the game so far, that can be saved and played again.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            version: REPLAY_VERSION,
+            seed: self.seed,
+            mode: self.mode,
+            arena: self.arena.clone(),
+            config: self.initial_config.clone(),
+            actions: self.recorded_actions.clone(),
+            config_changes: self.config_changes.clone(),
+        }
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.scripted_actions.is_some()
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Change the config during a game. What is already in play is kept, only new bombs, fires,
+    /// obstacles and power ups use the new values. Ignored when playing a replay.
+    pub fn set_config(&mut self, config: Config) {
+        if self.is_replay() {
+            return;
+        }
+        self.config_changes.push(ConfigChange {
+            time: self.time,
+            config: config.clone(),
+        });
+        self.config = config;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// The arena the game is played on, `None` for the usual empty board.
+    pub fn arena(&self) -> Option<&Arena> {
+        self.arena.as_ref()
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The first player, the only one in a solo game.
+    pub fn player(&self) -> &Player {
+        &self.players[0]
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn laser(&self) -> &Laser {
+        &self.laser
+    }
+
+    pub fn round(&self) -> u16 {
+        self.round
+    }
+
+    /// Score of all players together, including the score shared by a co-op team.
+    pub fn score(&self) -> u32 {
+        self.shared_score + self.players.iter().map(|player| player.score).sum::<u32>()
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state == State::Running
+    }
+
+    /// In a versus game that is over, the player left standing. `None` if both died at once.
+    pub fn winner(&self) -> Option<usize> {
+        if self.mode != Mode::Versus || self.is_running() {
+            return None;
+        }
+        let mut alive = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, player)| player.is_alive());
+        match (alive.next(), alive.next()) {
+            (Some((winner, _)), None) => Some(winner),
+            _ => None,
+        }
+    }
+
+    /// Simulated time since the start of the game, in milliseconds.
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn time_since_last_move(&self, player: usize) -> Option<u64> {
+        self.players[player]
+            .last_move
+            .map(|last_move| self.time - last_move)
+    }
+
+    pub fn bombs_available(&self, player: usize) -> usize {
+        let used = self
+            .board
+            .cells()
+            .filter(|(_, _, cell)| {
+                cell.bomb
+                    .as_ref()
+                    .map(|bomb| bomb.owner == player)
+                    .unwrap_or(false)
+            })
+            .count();
+        self.players[player].nb_bombs.saturating_sub(used)
+    }
+
+    /// Does `player` have bombs waiting to be set off with a remote.
+    pub fn has_waiting_bombs(&self, player: usize) -> bool {
+        !self.waiting_bombs(player).is_empty()
+    }
+
+    fn waiting_bombs(&self, player: usize) -> Vec<(usize, usize)> {
+        self.board
+            .cells()
+            .filter(|(_, _, cell)| {
+                cell.bomb
+                    .as_ref()
+                    .map(|bomb| bomb.owner == player && bomb.remote)
+                    .unwrap_or(false)
+            })
+            .map(|(x, y, _)| (x, y))
+            .collect()
+    }
+
+    /// Advance the game by `delta_seconds`. Actions, given with the index of the player doing
+    /// them, are applied at the start of the next tick.
+    pub fn step(&mut self, delta_seconds: f32, actions: &[(usize, PlayerAction)]) -> Vec<Event> {
+        self.pending_actions.extend_from_slice(actions);
+        self.accumulated_us += (delta_seconds * 1_000_000.) as u64;
+        while self.accumulated_us >= TICK_MS * 1000 {
+            self.accumulated_us -= TICK_MS * 1000;
+            let actions = std::mem::take(&mut self.pending_actions);
+            self.advance(&actions);
+        }
+        std::mem::take(&mut self.events)
+    }
+
+    /// Advance the game by exactly one tick.
+    pub fn tick(&mut self, actions: &[(usize, PlayerAction)]) -> Vec<Event> {
+        let mut pending = std::mem::take(&mut self.pending_actions);
+        pending.extend_from_slice(actions);
+        self.advance(&pending);
+        std::mem::take(&mut self.events)
+    }
+
+    /// Start noting what happens in the journal of this game, each entry with the time, round
+    /// and positions of when it happened.
+    pub fn start_journal(&mut self) {
+        self.journal = Some(vec![JournalEntry::start(self)]);
+    }
+
+    /// Journal entries noted since the last call, empty when the journal was not started.
+    pub fn take_journal(&mut self) -> Vec<JournalEntry> {
+        self.journal
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    fn emit(&mut self, event: Event) {
+        let entry = match self.journal {
+            Some(_) => JournalEntry::from_event(&event, self),
+            None => None,
+        };
+        if let (Some(journal), Some(entry)) = (self.journal.as_mut(), entry) {
+            journal.push(entry);
+        }
+        self.events.push(event);
+    }
+
+    fn advance(&mut self, actions: &[(usize, PlayerAction)]) {
+        if !self.is_running() {
+            return;
+        }
+        while let Some(change) = self.scripted_config_changes.front() {
+            if change.time > self.time {
+                break;
+            }
+            self.config = self.scripted_config_changes.pop_front().unwrap().config;
+        }
+        self.time += TICK_MS;
+        let time = self.time;
+
+        let scripted;
+        let actions = match self.scripted_actions.as_mut() {
+            Some(script) => {
+                let mut actions = vec![];
+                while let Some(recorded) = script.front() {
+                    if recorded.time > time {
+                        break;
+                    }
+                    actions.push((recorded.player, recorded.action));
+                    script.pop_front();
+                }
+                scripted = actions;
+                &scripted[..]
+            }
+            None => actions,
+        };
+        self.recorded_actions
+            .extend(actions.iter().map(|(player, action)| RecordedAction {
+                time,
+                player: *player,
+                action: *action,
+            }));
+
+        let mut moved = vec![false; self.players.len()];
+        for (player, action) in actions {
+            let player = *player;
+            if player >= self.players.len() || !self.players[player].is_alive() {
+                continue;
+            }
+            match action {
+                PlayerAction::PoseBomb => self.pose_bomb(player),
+                PlayerAction::Detonate => self.detonate(player),
+                PlayerAction::Move(direction) => {
+                    if !moved[player] && self.players[player].movement.is_none() {
+                        self.move_player(player, *direction);
+                        moved[player] = true;
+                    }
+                }
+            }
+        }
+
+        self.update_player();
+        self.update_laser();
+        self.spawn_obstacles();
+        self.update_bombs();
+        self.update_fires();
+        self.update_obstacles();
+        self.update_powerups();
+        self.update_revives();
+
+        if self.score_timer.tick(TICK_MS) {
+            let round = self.round as u32;
+            if self.mode == Mode::Coop {
+                self.shared_score += round;
+            } else {
+                for player in self.players.iter_mut().filter(|player| player.is_alive()) {
+                    player.score += round;
+                }
+            }
+        }
+
+        self.check_game_over();
+    }
+
+    /// Burn `player`, unless their shield absorbs the hit.
+    fn burn(&mut self, player: usize, cause: CauseOfDeath) {
+        let burned = &mut self.players[player];
+        if !burned.is_alive() || burned.protected.is_some() {
+            return;
+        }
+        if burned.shield {
+            burned.shield = false;
+            burned.protected = Some(Timer::from_millis(
+                seconds_to_millis(self.config.player_shield_timer),
+                false,
+            ));
+            self.emit(Event::ShieldUsed { player });
+            return;
+        }
+        self.kill(player, cause);
+    }
+
+    fn kill(&mut self, player: usize, cause: CauseOfDeath) {
+        let down = &mut self.players[player];
+        if down.is_alive() {
+            down.death = Some(cause);
+            down.died_at = self.time;
+            // a downed player stays where they were burned until revived
+            down.movement = None;
+            down.move_timer = None;
+            self.emit(Event::PlayerDied { player, cause });
+        }
+    }
+
+    fn check_game_over(&mut self) {
+        if !self.is_running() {
+            return;
+        }
+        let over = match self.mode {
+            // in co-op a downed player can still be revived while their partner is standing
+            Mode::Coop => self.players.iter().all(|player| !player.is_alive()),
+            Mode::Solo | Mode::Versus => self.players.iter().any(|player| !player.is_alive()),
+        };
+        if !over {
+            return;
+        }
+        let first_down = self
+            .players
+            .iter()
+            .filter(|player| !player.is_alive())
+            .min_by_key(|player| player.died_at);
+        if let Some(cause) = first_down.and_then(|player| player.death) {
+            self.state = State::Lost(cause);
+            self.emit(Event::Lost(cause));
+        }
+    }
+
+    fn update_revives(&mut self) {
+        if self.mode != Mode::Coop {
+            return;
+        }
+        let revive_time = seconds_to_millis(self.config.coop_revive_time);
+        let standing = self
+            .players
+            .iter()
+            .filter(|player| player.is_alive())
+            .map(|player| (player.x, player.y))
+            .collect::<Vec<_>>();
+        let mut revived = vec![];
+        for (index, player) in self.players.iter_mut().enumerate() {
+            if player.is_alive() {
+                continue;
+            }
+            let next_to = |(x, y): &(usize, usize)| {
+                let distance =
+                    (*x as i32 - player.x as i32).abs() + (*y as i32 - player.y as i32).abs();
+                distance <= 1
+            };
+            if !standing.iter().any(next_to) {
+                player.revive = None;
+                continue;
+            }
+            let timer = player
+                .revive
+                .get_or_insert_with(|| Timer::from_millis(revive_time, false));
+            if timer.tick(TICK_MS) {
+                revived.push(index);
+            }
+        }
+        for player in revived {
+            let revived = &mut self.players[player];
+            revived.death = None;
+            revived.revive = None;
+            revived.last_move = Some(self.time);
+            self.emit(Event::PlayerRevived { player });
+        }
+    }
+
+    fn pose_bomb(&mut self, player: usize) {
+        if self.bombs_available(player) == 0 {
+            return;
+        }
+        let owner = &self.players[player];
+        let (x, y) = (owner.x, owner.y);
+        let bomb = Bomb {
+            damage: owner.bomb_damage,
+            range: owner.bomb_range,
+            state: BombState::Fuse,
+            timer: Timer::from_millis(owner.bomb_speed / 2, false),
+            owner: player,
+            pierce: owner.pierce,
+            remote: owner.remote,
+            sliding: None,
+        };
+        let cell = self.board.cell_mut(x, y);
+        if cell.bomb.is_none() {
+            cell.bomb = Some(bomb);
+            self.emit(Event::BombPlaced { player, x, y });
+        }
+    }
+
+    fn detonate(&mut self, player: usize) {
+        for (x, y) in self.waiting_bombs(player) {
+            self.explode(x, y, false);
+        }
+    }
+
+    fn move_player(&mut self, player: usize, direction: MoveDirection) {
+        let (width, height) = (self.board.width(), self.board.height());
+        let change = direction.change();
+        let moving = &self.players[player];
+        let x = moving.x as i32 + change.0;
+        let y = moving.y as i32 + change.1;
+
+        let movement = if y < 0 || y >= height as i32 {
+            PlayerMove::Bump(direction)
+        } else if x < 0 || x >= width as i32 {
+            let to = if x < 0 { width - 1 } else { 0 };
+            if !self.board.can_teleport(y as usize) || self.board.cell(to, y as usize).is_blocking()
+            {
+                PlayerMove::Bump(direction)
+            } else {
+                PlayerMove::Teleport(to, y as usize)
+            }
+        } else if self.board.cell(x as usize, y as usize).is_blocking() {
+            PlayerMove::Bump(direction)
+        } else {
+            PlayerMove::Walk(direction, x as usize, y as usize)
+        };
+        if let PlayerMove::Walk(direction, x, y) = movement {
+            if moving.kick && self.board.cell(x, y).bomb.is_some() {
+                self.kick_bomb(player, x, y, direction);
+            }
+        }
+
+        let moving = &mut self.players[player];
+        let remaining_move = moving
+            .move_timer
+            .as_ref()
+            .map(|timer| timer.remaining())
+            .unwrap_or(0);
+        let move_delay = moving.speed + remaining_move;
+        let buffer_delay = move_delay * 7 / 10;
+
+        let to = match movement {
+            PlayerMove::Walk(direction, x, y) => {
+                match direction {
+                    MoveDirection::Left => moving.direction = FacingDirection::Left,
+                    MoveDirection::Right => moving.direction = FacingDirection::Right,
+                    _ => (),
+                }
+                (x, y)
+            }
+            PlayerMove::Teleport(x, y) => (x, y),
+            PlayerMove::Bump(_) => (moving.x, moving.y),
+        };
+
+        let from = (moving.x, moving.y);
+        moving.last_move = Some(self.time);
+        moving.movement = Some(Movement {
+            to,
+            allow_new_move: Timer::from_millis(buffer_delay, false),
+            update_position: Timer::from_millis(move_delay / 2, false),
+        });
+        moving.move_timer = Some(Timer::from_millis(move_delay, false));
+        self.emit(Event::PlayerMoved {
+            player,
+            from,
+            movement,
+            duration: move_delay,
+        });
+    }
+
+    fn kick_bomb(&mut self, player: usize, x: usize, y: usize, direction: MoveDirection) {
+        if self.slide_destination(x, y, direction).is_none() {
+            return;
+        }
+        let timer = Timer::from_millis(self.config.player_kick_speed, true);
+        if let Some(bomb) = self.board.cell_mut(x, y).bomb.as_mut() {
+            bomb.sliding = Some((direction, timer));
+            self.emit(Event::BombKicked {
+                player,
+                x,
+                y,
+                direction,
+            });
+        }
+    }
+
+    /// Where a bomb sliding from `(x, y)` goes next. Bombs don't teleport, and stop at anything
+    /// in their way, including players and other bombs.
+    fn slide_destination(
+        &self,
+        x: usize,
+        y: usize,
+        direction: MoveDirection,
+    ) -> Option<(usize, usize)> {
+        let change = direction.change();
+        let (x, y) = (x as i32 + change.0, y as i32 + change.1);
+        if x < 0 || y < 0 || x >= self.board.width() as i32 || y >= self.board.height() as i32 {
+            return None;
+        }
+        let (x, y) = (x as usize, y as usize);
+        let cell = self.board.cell(x, y);
+        let player_there = self
+            .players
+            .iter()
+            .any(|player| (player.x, player.y) == (x, y));
+        if cell.is_blocking() || cell.bomb.is_some() || player_there {
+            None
+        } else {
+            Some((x, y))
+        }
+    }
+
+    fn slide_bomb(&mut self, x: usize, y: usize) {
+        let direction = match self.board.cell(x, y).bomb.as_ref() {
+            Some(Bomb {
+                sliding: Some((direction, _)),
+                ..
+            }) => *direction,
+            _ => return,
+        };
+        match self.slide_destination(x, y, direction) {
+            Some((to_x, to_y)) => {
+                let bomb = self.board.cell_mut(x, y).bomb.take();
+                self.board.cell_mut(to_x, to_y).bomb = bomb;
+            }
+            None => {
+                if let Some(bomb) = self.board.cell_mut(x, y).bomb.as_mut() {
+                    bomb.sliding = None;
+                }
+            }
+        }
+    }
+
+    fn update_player(&mut self) {
+        for player in self.players.iter_mut() {
+            if let Some(mut protected) = player.protected.take() {
+                if !protected.tick(TICK_MS) {
+                    player.protected = Some(protected);
+                }
+            }
+            if let Some(timer) = player.move_timer.as_mut() {
+                timer.tick(TICK_MS);
+            }
+            if let Some(mut movement) = player.movement.take() {
+                if movement.update_position.tick(TICK_MS) {
+                    player.x = movement.to.0;
+                    player.y = movement.to.1;
+                }
+                if !movement.allow_new_move.tick(TICK_MS) {
+                    player.movement = Some(movement);
+                }
+            }
+        }
+    }
+
+    fn update_laser(&mut self) {
+        for player in 0..self.players.len() {
+            if self.laser.x == self.players[player].x + 2 {
+                self.kill(player, CauseOfDeath::Laser);
+            }
+        }
+        if !self.laser_timer.tick(TICK_MS) {
+            return;
+        }
+        let (width, height) = (self.board.width(), self.board.height());
+        if self.laser.x >= 1 && self.laser.x - 1 < width {
+            for y in 0..height {
+                let cell = self.board.cell_mut(self.laser.x - 1, y);
+                if cell.fire.is_none() && !cell.wall {
+                    cell.fire = Some(Fire {
+                        damage: self.config.laser_fire_damage,
+                        timer: Timer::from_millis(
+                            seconds_to_millis(self.config.laser_fire_timer),
+                            false,
+                        ),
+                        source: FireSource::Laser,
+                    });
+                }
+            }
+        }
+
+        self.laser.x += 1;
+        if self.laser.x == width + 1 {
+            self.laser.x = 0;
+        }
+        self.emit(Event::LaserMoved {
+            x: self.laser.x,
+            duration: self.laser.speed,
+        });
+        if self.laser.x == width / 2 {
+            self.new_round();
+        }
+        self.laser_timer.duration = self.laser.speed;
+    }
+
+    fn new_round(&mut self) {
+        self.round += 1;
+        self.emit(Event::NewRound);
+
+        match self.config.laser_progression {
+            LaserProgression::Random => {
+                let powerup = if self.round == 1 {
+                    // avoid power up ObstacleStrengh on first round
+                    LaserPowerUp::iter()
+                        .filter(|bonus| *bonus != LaserPowerUp::ObstacleStrengh)
+                        .choose(&mut self.rng)
+                        .unwrap()
+                } else {
+                    LaserPowerUp::iter().choose(&mut self.rng).unwrap()
+                };
+                self.laser.power_up(powerup, &self.config);
+                self.emit(Event::LaserBonus(powerup));
+            }
+            LaserProgression::Schedule => self.laser.follow_schedule(self.round, &self.config),
+        }
+    }
+
+    fn spawn_obstacles(&mut self) {
+        if !self.obstacle_timer.tick(TICK_MS) {
+            return;
+        }
+        let (width, height) = (self.board.width(), self.board.height());
+        let players = self
+            .players
+            .iter()
+            .map(|player| (player.x, player.y))
+            .collect::<Vec<_>>();
+        let moving_to = self
+            .players
+            .iter()
+            .filter_map(|player| player.movement.as_ref().map(|movement| movement.to))
+            .collect::<Vec<_>>();
+        let nb_obstacles = self.laser.nb_obstacles;
+        let kinds = ObstacleKind::iter()
+            .map(|kind| (kind, self.config.obstacle_weights.weight(kind, self.round)))
+            .collect::<Vec<_>>();
+        let rng = &mut self.rng;
+
+        let candidates = std::iter::repeat_with(|| {
+            let x = rng.gen_range(1, width);
+            let y = rng.gen_range(0, height);
+            (x, y)
+        })
+        .filter(|position| !players.contains(position))
+        .take(nb_obstacles * 2)
+        .filter(|position| !moving_to.contains(position))
+        .collect::<Vec<_>>();
+
+        let mut spawned = 0;
+        for (x, y) in candidates {
+            if spawned == nb_obstacles {
+                break;
+            }
+            let cell = self.board.cell_mut(x, y);
+            if !cell.is_occupied() {
+                let kind = kinds
+                    .choose_weighted(rng, |(_, weight)| *weight)
+                    .map(|(kind, _)| *kind)
+                    .unwrap_or(ObstacleKind::Crate);
+                cell.obstacle = Some(Obstacle::with_kind(kind, self.laser.obstacle_strength));
+                spawned += 1;
+            }
+        }
+        self.obstacle_timer.duration = self.laser.spawn_obstacles_delay as u64;
+    }
+
+    fn update_bombs(&mut self) {
+        let mut explosions = vec![];
+        let mut slides = vec![];
+        for (x, y) in self.board.positions().collect::<Vec<_>>() {
+            let cell = self.board.cell_mut(x, y);
+            let chained = cell.fire.is_some();
+            if let Some(bomb) = cell.bomb.as_mut() {
+                let finished = !bomb.remote && bomb.timer.tick(TICK_MS);
+                if finished && bomb.state == BombState::Fuse {
+                    bomb.state = BombState::Flash;
+                    bomb.timer.reset();
+                } else if finished && bomb.state == BombState::Flash || chained {
+                    explosions.push((x, y, chained));
+                }
+                if let Some((_, timer)) = bomb.sliding.as_mut() {
+                    if timer.tick(TICK_MS) {
+                        slides.push((x, y));
+                    }
+                }
+            }
+        }
+        for (x, y, chained) in explosions {
+            self.explode(x, y, chained);
+        }
+        for (x, y) in slides {
+            self.slide_bomb(x, y);
+        }
+    }
+
+    fn explode(&mut self, x: usize, y: usize, chained: bool) {
+        let bomb = match self.board.cell_mut(x, y).bomb.take() {
+            Some(bomb) => bomb,
+            None => return,
+        };
+        self.emit(Event::BombExploded {
+            player: bomb.owner,
+            x,
+            y,
+            chained,
+        });
+
+        self.spread_fire(
+            x,
+            y,
+            bomb.range,
+            bomb.damage,
+            FireSource::Bomb(bomb.owner),
+            bomb.pierce,
+        );
+    }
+
+    /// Set cells on fire in a cross around `(x, y)`, like a bomb exploding. Fire stops at the
+    /// first wall in each direction, and at the first obstacle unless it `pierce`s them.
+    fn spread_fire(
+        &mut self,
+        x: usize,
+        y: usize,
+        range: usize,
+        damage: usize,
+        source: FireSource,
+        pierce: bool,
+    ) {
+        let (width, height) = (self.board.width(), self.board.height());
+        let fire_timer = seconds_to_millis(self.config.player_bomb_fire_timer);
+        let board = &mut self.board;
+        let mut set_on_fire = |x, y| {
+            let cell = board.cell_mut(x, y);
+            if cell.wall {
+                return true;
+            }
+            cell.fire = Some(Fire {
+                damage,
+                timer: Timer::from_millis(fire_timer, false),
+                source,
+            });
+            cell.obstacle.is_some() && !pierce
+        };
+
+        let mut stop_right = false;
+        let mut stop_left = false;
+        for i in 0..=range {
+            if !stop_right && x + i < width {
+                stop_right = set_on_fire(x + i, y);
+            }
+            if !stop_left && x as i32 - i as i32 > 0 {
+                stop_left = set_on_fire(x - i, y);
+            }
+        }
+
+        let mut stop_top = false;
+        let mut stop_bottom = false;
+        for j in 0..=range {
+            if !stop_top && y + j < height {
+                stop_top = set_on_fire(x, y + j);
+            }
+            if !stop_bottom && y as i32 - j as i32 >= 0 {
+                stop_bottom = set_on_fire(x, y - j);
+            }
+        }
+    }
+
+    fn update_fires(&mut self) {
+        let players = self
+            .players
+            .iter()
+            .map(|player| (player.x, player.y))
+            .collect::<Vec<_>>();
+        let mut burned = vec![];
+        let mut destroyed = vec![];
+        for (x, y) in self.board.positions().collect::<Vec<_>>() {
+            let cell = self.board.cell_mut(x, y);
+            let fire = match cell.fire.as_mut() {
+                Some(fire) => fire,
+                None => continue,
+            };
+            for (player, position) in players.iter().enumerate() {
+                if (x, y) == *position {
+                    burned.push((
+                        player,
+                        match fire.source.player() {
+                            None => CauseOfDeath::LaserFire,
+                            Some(owner) if owner == player => CauseOfDeath::BombFire,
+                            Some(_) => CauseOfDeath::OpponentBomb,
+                        },
+                    ));
+                }
+            }
+            if fire.timer.tick(TICK_MS) {
+                let fire = cell.fire.take().unwrap();
+                if let Some(obstacle) = cell.obstacle.as_mut() {
+                    let immune =
+                        obstacle.kind == ObstacleKind::Metal && fire.source == FireSource::Laser;
+                    if !immune {
+                        obstacle.remaining_life -= fire.damage as i32;
+                        obstacle.regeneration = 0;
+                    }
+                    if obstacle.remaining_life <= 0 {
+                        destroyed.push((x, y, fire.source, obstacle.kind));
+                        cell.obstacle = None;
+                    }
+                }
+            }
+        }
+        for (player, cause) in burned {
+            self.burn(player, cause);
+        }
+        for (x, y, source, kind) in destroyed {
+            self.emit(Event::ObstacleDestroyed { x, y, source, kind });
+            if kind == ObstacleKind::Barrel {
+                self.spread_fire(
+                    x,
+                    y,
+                    self.config.obstacle_barrel_range,
+                    self.config.obstacle_barrel_damage,
+                    FireSource::Barrel(source.player()),
+                    false,
+                );
+            } else if kind == ObstacleKind::Treasure
+                || self.rng.gen_bool(self.config.player_powerup_chance)
+            {
+                let weights = &self.config.player_powerup_weights;
+                let powerups = PlayerPowerUp::iter()
+                    .map(|powerup| (powerup, weights.weight(powerup)))
+                    .collect::<Vec<_>>();
+                let powerup = match powerups.choose_weighted(&mut self.rng, |(_, weight)| *weight) {
+                    Ok((powerup, _)) => *powerup,
+                    Err(_) => continue,
+                };
+                self.board.cell_mut(x, y).powerup = Some(PowerUp {
+                    powerup,
+                    timer: Timer::from_millis(seconds_to_millis(self.config.powerup_timer), false),
+                    used: false,
+                });
+            }
+        }
+    }
+
+    fn update_obstacles(&mut self) {
+        let delay = seconds_to_millis(self.config.obstacle_regeneration_delay);
+        for (x, y) in self.board.positions().collect::<Vec<_>>() {
+            let obstacle = match self.board.cell_mut(x, y).obstacle.as_mut() {
+                Some(obstacle) if obstacle.kind == ObstacleKind::Regenerating => obstacle,
+                _ => continue,
+            };
+            if obstacle.remaining_life < obstacle.original_life {
+                obstacle.regeneration += TICK_MS;
+                if obstacle.regeneration >= delay {
+                    obstacle.remaining_life += 1;
+                    obstacle.regeneration = 0;
+                }
+            }
+        }
+    }
+
+    fn update_powerups(&mut self) {
+        let players = self
+            .players
+            .iter()
+            .map(|player| (player.x, player.y, player.is_alive(), player.speed))
+            .collect::<Vec<_>>();
+        let mut taken = vec![];
+        for (x, y) in self.board.positions().collect::<Vec<_>>() {
+            let cell = self.board.cell_mut(x, y);
+            let powerup = match cell.powerup.as_mut() {
+                Some(powerup) => powerup,
+                None => continue,
+            };
+            for (player, (px, py, alive, speed)) in players.iter().enumerate() {
+                if !powerup.used && *alive && (x, y) == (*px, *py) {
+                    powerup.timer = Timer::from_millis(speed * 3 / 4, false);
+                    powerup.used = true;
+                    taken.push((player, powerup.powerup));
+                }
+            }
+            if powerup.timer.tick(TICK_MS) {
+                cell.powerup = None;
+            }
+        }
+        for (player, powerup) in taken {
+            self.apply_powerup(player, powerup);
+        }
+    }
+
+    fn apply_powerup(&mut self, player: usize, powerup: PlayerPowerUp) {
+        let config = &self.config;
+        let round = self.round as u32;
+        let taker = &mut self.players[player];
+        match powerup {
+            PlayerPowerUp::Score => taker.score += round * config.player_powerup_score,
+            PlayerPowerUp::BombCount => taker.nb_bombs += config.player_powerup_bomb_count,
+            PlayerPowerUp::BombDamage => taker.bomb_damage += config.player_powerup_bomb_damage,
+            PlayerPowerUp::BombRange => taker.bomb_range += config.player_powerup_bomb_range,
+            PlayerPowerUp::BombSpeed => {
+                taker.bomb_speed =
+                    (taker.bomb_speed as f64 * config.player_powerup_bomb_speed) as u64
+            }
+            PlayerPowerUp::Kick => taker.kick = true,
+            PlayerPowerUp::Remote => taker.remote = true,
+            PlayerPowerUp::Shield => taker.shield = true,
+            PlayerPowerUp::PlayerSpeed => {
+                // at most twice as fast as when starting
+                taker.speed = ((taker.speed as f64 * config.player_powerup_speed) as u64)
+                    .max(config.player_speed / 2)
+                    .max(1)
+            }
+            PlayerPowerUp::Pierce => taker.pierce = true,
+        }
+        self.emit(Event::PlayerBonus { player, powerup });
+    }
+}
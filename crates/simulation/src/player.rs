@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{CauseOfDeath, Config, Timer};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl MoveDirection {
+    pub fn change(self) -> (i32, i32) {
+        match self {
+            MoveDirection::Left => (-1, 0),
+            MoveDirection::Right => (1, 0),
+            MoveDirection::Up => (0, 1),
+            MoveDirection::Down => (0, -1),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerAction {
+    PoseBomb,
+    /// Set off the bombs waiting for their remote
+    Detonate,
+    Move(MoveDirection),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, enum_utils::IterVariants)]
+pub enum PlayerPowerUp {
+    Score,
+    BombCount,
+    BombDamage,
+    BombRange,
+    BombSpeed,
+    /// Walking into a bomb kicks it, sliding until something blocks it
+    Kick,
+    /// Bombs wait for the player to use the detonate action to explode
+    Remote,
+    /// Absorbs one fire hit
+    Shield,
+    PlayerSpeed,
+    /// Bomb fire goes through obstacles
+    Pierce,
+}
+
+/// How often each power up drops from a destroyed obstacle, compared to the others.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PlayerPowerUpWeights {
+    pub score: f64,
+    pub bomb_count: f64,
+    pub bomb_damage: f64,
+    pub bomb_range: f64,
+    pub bomb_speed: f64,
+    pub kick: f64,
+    pub remote: f64,
+    pub shield: f64,
+    pub player_speed: f64,
+    pub pierce: f64,
+}
+
+impl Default for PlayerPowerUpWeights {
+    fn default() -> Self {
+        PlayerPowerUpWeights {
+            score: 1.,
+            bomb_count: 1.,
+            bomb_damage: 1.,
+            bomb_range: 1.,
+            bomb_speed: 1.,
+            kick: 0.5,
+            remote: 0.3,
+            shield: 0.5,
+            player_speed: 0.7,
+            pierce: 0.3,
+        }
+    }
+}
+
+impl PlayerPowerUpWeights {
+    pub fn weight(&self, powerup: PlayerPowerUp) -> f64 {
+        match powerup {
+            PlayerPowerUp::Score => self.score,
+            PlayerPowerUp::BombCount => self.bomb_count,
+            PlayerPowerUp::BombDamage => self.bomb_damage,
+            PlayerPowerUp::BombRange => self.bomb_range,
+            PlayerPowerUp::BombSpeed => self.bomb_speed,
+            PlayerPowerUp::Kick => self.kick,
+            PlayerPowerUp::Remote => self.remote,
+            PlayerPowerUp::Shield => self.shield,
+            PlayerPowerUp::PlayerSpeed => self.player_speed,
+            PlayerPowerUp::Pierce => self.pierce,
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut problems = vec![];
+        for (key, value) in &[
+            ("player_powerup_weights.score", self.score),
+            ("player_powerup_weights.bomb_count", self.bomb_count),
+            ("player_powerup_weights.bomb_damage", self.bomb_damage),
+            ("player_powerup_weights.bomb_range", self.bomb_range),
+            ("player_powerup_weights.bomb_speed", self.bomb_speed),
+            ("player_powerup_weights.kick", self.kick),
+            ("player_powerup_weights.remote", self.remote),
+            ("player_powerup_weights.shield", self.shield),
+            ("player_powerup_weights.player_speed", self.player_speed),
+            ("player_powerup_weights.pierce", self.pierce),
+        ] {
+            if *value < 0. {
+                problems.push((*key, "must not be negative".to_string()));
+            }
+        }
+        problems
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FacingDirection {
+    Left,
+    Right,
+}
+
+/// Result of a move command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerMove {
+    Walk(MoveDirection, usize, usize),
+    Teleport(usize, usize),
+    Bump(MoveDirection),
+}
+
+#[derive(Debug, Clone)]
+pub struct Movement {
+    pub to: (usize, usize),
+    pub(crate) allow_new_move: Timer,
+    pub(crate) update_position: Timer,
+}
+
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub x: usize,
+    pub y: usize,
+    pub direction: FacingDirection,
+    pub nb_bombs: usize,
+    pub bomb_range: usize,
+    pub bomb_damage: usize,
+    pub bomb_speed: u64,
+    pub speed: u64,
+    pub kick: bool,
+    pub remote: bool,
+    pub shield: bool,
+    pub pierce: bool,
+    pub movement: Option<Movement>,
+    pub score: u32,
+    pub death: Option<CauseOfDeath>,
+    pub(crate) move_timer: Option<Timer>,
+    pub(crate) last_move: Option<u64>,
+    /// When the player went down, to know who was first
+    pub(crate) died_at: u64,
+    pub(crate) revive: Option<Timer>,
+    /// Can't be burned until this runs out, after the shield absorbed a hit
+    pub(crate) protected: Option<Timer>,
+}
+
+impl Player {
+    pub fn new(config: &Config, x: usize, y: usize) -> Self {
+        Player {
+            x,
+            y,
+            direction: FacingDirection::Right,
+            nb_bombs: config.player_nb_bombs,
+            bomb_range: config.player_bomb_range,
+            bomb_damage: config.player_bomb_damage,
+            bomb_speed: config.player_bomb_speed,
+            speed: config.player_speed,
+            kick: false,
+            remote: false,
+            shield: false,
+            pierce: false,
+            movement: None,
+            score: 0,
+            death: None,
+            move_timer: None,
+            last_move: None,
+            died_at: 0,
+            revive: None,
+            protected: None,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.death.is_none()
+    }
+
+    /// How far along a partner is in reviving this downed player, between 0 and 1.
+    pub fn revive_progress(&self) -> Option<f32> {
+        self.revive
+            .as_ref()
+            .map(|timer| timer.elapsed as f32 / timer.duration.max(1) as f32)
+    }
+}
@@ -0,0 +1,60 @@
+use std::{error::Error, fs::File, io::BufReader, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Arena, Config, Mode, PlayerAction};
+
+/// Version of the replay format, bumped each time the rules or the format change in a way that
+/// would make older replays play out differently.
+pub const REPLAY_VERSION: u32 = 4;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RecordedAction {
+    /// Simulated time at which the action was applied, in milliseconds.
+    pub time: u64,
+    #[serde(default)]
+    pub player: usize,
+    pub action: PlayerAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigChange {
+    /// Simulated time after which the config was changed, in milliseconds.
+    pub time: u64,
+    pub config: Config,
+}
+
+/// Everything needed to play a game again exactly as it happened.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    #[serde(default)]
+    pub mode: Mode,
+    /// Arena the game was played on, the usual empty board when missing
+    #[serde(default)]
+    pub arena: Option<Arena>,
+    pub config: Config,
+    pub actions: Vec<RecordedAction>,
+    #[serde(default)]
+    pub config_changes: Vec<ConfigChange>,
+}
+
+impl Replay {
+    pub fn load(path: impl AsRef<Path>) -> Result<Replay, Box<dyn Error>> {
+        let replay: Replay = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        if replay.version != REPLAY_VERSION {
+            return Err(format!(
+                "replay version {} is not supported, expected {}",
+                replay.version, REPLAY_VERSION
+            )
+            .into());
+        }
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        serde_json::to_writer(File::create(path)?, self)?;
+        Ok(())
+    }
+}
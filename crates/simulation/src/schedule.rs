@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ObstacleKind;
+
+/// How the laser gets harder at each new round.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LaserProgression {
+    /// One random laser power up each round
+    #[default]
+    Random,
+    /// Laser values follow `laser_schedule`
+    Schedule,
+}
+
+/// How a value changes from round to round.
+///
+/// The first rounds take their value from `values`. After that, starting from the last of those
+/// values (or the value from the config if there are none), each round adds `step` then multiplies
+/// by `factor`. The result is kept between `min` and `max`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Curve {
+    pub values: Vec<f64>,
+    pub step: f64,
+    pub factor: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve {
+            values: vec![],
+            step: 0.,
+            factor: 1.,
+            min: None,
+            max: None,
+        }
+    }
+}
+
+impl Curve {
+    /// Value at `round`, from `start` being the value before the first round.
+    pub fn at(&self, round: u16, start: f64) -> f64 {
+        let round = round as usize;
+        let value = if round >= 1 && round <= self.values.len() {
+            self.values[round - 1]
+        } else {
+            let (mut value, from) = match self.values.last() {
+                Some(last) => (*last, self.values.len()),
+                None => (start, 0),
+            };
+            for _ in from..round {
+                value = (value + self.step) * self.factor;
+            }
+            value
+        };
+        let value = self.min.map_or(value, |min| value.max(min));
+        self.max.map_or(value, |max| value.min(max))
+    }
+
+    pub(crate) fn validate(&self, key: &'static str) -> Vec<(&'static str, String)> {
+        let mut problems = vec![];
+        if self.factor <= 0. {
+            problems.push((key, "factor must be greater than 0".to_string()));
+        }
+        if self.values.iter().any(|value| *value < 0.) {
+            problems.push((key, "values must not be negative".to_string()));
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            if min > max {
+                problems.push((
+                    key,
+                    format!("min ({}) must not be above max ({})", min, max),
+                ));
+            }
+        }
+        problems
+    }
+}
+
+/// Laser values for each round, used when `laser_progression` is `schedule`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct LaserSchedule {
+    /// milliseconds for the laser to move, starting from `laser_speed`
+    pub speed: Curve,
+    /// milliseconds between obstacle spawns, starting from `laser_spawn_obstacles_delay`
+    pub spawn_obstacles_delay: Curve,
+    /// obstacles spawned at once, starting from `laser_nb_obstacles`
+    pub nb_obstacles: Curve,
+    /// life of new obstacles, starting from `laser_obstacle_strength`
+    pub obstacle_strength: Curve,
+}
+
+impl LaserSchedule {
+    pub(crate) fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut problems = vec![];
+        problems.extend(self.speed.validate("laser_schedule.speed"));
+        problems.extend(
+            self.spawn_obstacles_delay
+                .validate("laser_schedule.spawn_obstacles_delay"),
+        );
+        problems.extend(self.nb_obstacles.validate("laser_schedule.nb_obstacles"));
+        problems.extend(
+            self.obstacle_strength
+                .validate("laser_schedule.obstacle_strength"),
+        );
+        problems
+    }
+}
+
+/// How often each kind of obstacle spawns at each round, compared to the others. Curves start
+/// from 0.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ObstacleWeights {
+    pub crates: Curve,
+    pub barrels: Curve,
+    pub metal: Curve,
+    pub regenerating: Curve,
+    pub treasure: Curve,
+}
+
+impl Default for ObstacleWeights {
+    fn default() -> Self {
+        let from_round = |round: usize, step: f64, max: f64| Curve {
+            values: vec![0.; round - 1],
+            step,
+            max: Some(max),
+            ..Default::default()
+        };
+        ObstacleWeights {
+            crates: Curve {
+                values: vec![1.],
+                ..Default::default()
+            },
+            barrels: from_round(2, 0.05, 0.3),
+            metal: from_round(3, 0.05, 0.3),
+            regenerating: from_round(4, 0.05, 0.3),
+            treasure: Curve {
+                values: vec![0.05],
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl ObstacleWeights {
+    fn curve(&self, kind: ObstacleKind) -> &Curve {
+        match kind {
+            ObstacleKind::Crate => &self.crates,
+            ObstacleKind::Barrel => &self.barrels,
+            ObstacleKind::Metal => &self.metal,
+            ObstacleKind::Regenerating => &self.regenerating,
+            ObstacleKind::Treasure => &self.treasure,
+        }
+    }
+
+    pub fn weight(&self, kind: ObstacleKind, round: u16) -> f64 {
+        self.curve(kind).at(round, 0.).max(0.)
+    }
+
+    pub(crate) fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut problems = vec![];
+        problems.extend(self.crates.validate("obstacle_weights.crates"));
+        problems.extend(self.barrels.validate("obstacle_weights.barrels"));
+        problems.extend(self.metal.validate("obstacle_weights.metal"));
+        problems.extend(self.regenerating.validate("obstacle_weights.regenerating"));
+        problems.extend(self.treasure.validate("obstacle_weights.treasure"));
+        problems
+    }
+}
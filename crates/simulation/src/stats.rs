@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+use crate::{CauseOfDeath, Event, FireSource, Mode, ObstacleKind, Simulation};
+
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct PlayerStats {
+    pub bomb_placed: u16,
+    pub obstacle_destroyed: u16,
+    pub bomb_chained: u16,
+    pub score_first_bomb: u32,
+    /// Last time the player was burned, even if they were revived after
+    pub cause_of_death: Option<CauseOfDeath>,
+    pub revived: u16,
+}
+
+/// What happened during a game, built from its events.
+#[derive(Serialize, Debug, Default, Clone)]
+pub struct GameStats {
+    pub players: Vec<PlayerStats>,
+    pub obstacle_destroyed_by_laser: u16,
+    /// Obstacles destroyed by barrels the laser set off
+    pub obstacle_destroyed_by_barrel: u16,
+    /// Obstacles destroyed by players or the laser, by kind
+    pub crate_destroyed: u16,
+    pub barrel_destroyed: u16,
+    pub metal_destroyed: u16,
+    pub regenerating_destroyed: u16,
+    pub treasure_destroyed: u16,
+}
+
+impl GameStats {
+    fn player(&mut self, player: usize) -> &mut PlayerStats {
+        if self.players.len() <= player {
+            self.players.resize_with(player + 1, PlayerStats::default);
+        }
+        &mut self.players[player]
+    }
+
+    pub fn of(&self, player: usize) -> PlayerStats {
+        self.players.get(player).cloned().unwrap_or_default()
+    }
+
+    /// Take into account an event returned by a step of `sim`.
+    pub fn record(&mut self, event: &Event, sim: &Simulation) {
+        match event {
+            Event::PlayerDied { player, cause } => {
+                self.player(*player).cause_of_death = Some(*cause)
+            }
+            Event::PlayerRevived { player } => self.player(*player).revived += 1,
+            Event::BombPlaced { player, .. } => {
+                let score = if sim.mode() == Mode::Coop {
+                    sim.score()
+                } else {
+                    sim.players()[*player].score
+                };
+                let player_stats = self.player(*player);
+                player_stats.bomb_placed += 1;
+                if player_stats.score_first_bomb == 0 {
+                    player_stats.score_first_bomb = score;
+                }
+            }
+            Event::BombExploded {
+                player,
+                chained: true,
+                ..
+            } => self.player(*player).bomb_chained += 1,
+            Event::ObstacleDestroyed { source, kind, .. } => {
+                match source {
+                    FireSource::Laser => self.obstacle_destroyed_by_laser += 1,
+                    FireSource::Barrel(None) => self.obstacle_destroyed_by_barrel += 1,
+                    FireSource::Bomb(player) | FireSource::Barrel(Some(player)) => {
+                        self.player(*player).obstacle_destroyed += 1
+                    }
+                }
+                *self.destroyed(*kind) += 1;
+            }
+            _ => (),
+        }
+    }
+
+    fn destroyed(&mut self, kind: ObstacleKind) -> &mut u16 {
+        match kind {
+            ObstacleKind::Crate => &mut self.crate_destroyed,
+            ObstacleKind::Barrel => &mut self.barrel_destroyed,
+            ObstacleKind::Metal => &mut self.metal_destroyed,
+            ObstacleKind::Regenerating => &mut self.regenerating_destroyed,
+            ObstacleKind::Treasure => &mut self.treasure_destroyed,
+        }
+    }
+}
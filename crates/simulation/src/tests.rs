@@ -0,0 +1,463 @@
+use crate::*;
+
+fn simulation() -> Simulation {
+    Simulation::new(Config::default(), 0)
+}
+
+fn flashing_bomb(owner: usize, range: usize) -> Bomb {
+    Bomb {
+        damage: 1,
+        range,
+        state: BombState::Flash,
+        timer: Timer::from_millis(1000, false),
+        owner,
+        pierce: false,
+        remote: false,
+        sliding: None,
+    }
+}
+
+/// Move the first player and wait for the move to be over, returning how they moved.
+fn move_first_player(sim: &mut Simulation, direction: MoveDirection) -> PlayerMove {
+    let events = sim.tick(&[(0, PlayerAction::Move(direction))]);
+    while sim.player().movement.is_some() {
+        sim.tick(&[]);
+    }
+    events
+        .into_iter()
+        .find_map(|event| match event {
+            Event::PlayerMoved { movement, .. } => Some(movement),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn bomb_fire_stops_at_walls_and_crates() {
+    let mut sim = simulation();
+    let (x, y) = (10, 5);
+    sim.board.cell_mut(13, y).wall = true;
+    sim.board.cell_mut(x, 7).obstacle = Some(Obstacle::new(5));
+    sim.board.cell_mut(x, y).bomb = Some(flashing_bomb(0, 4));
+
+    sim.explode(x, y, false);
+
+    let board = sim.board();
+    let on_fire = |x, y| board.cell(x, y).fire.is_some();
+    assert!(board.cell(x, y).bomb.is_none());
+    assert!(on_fire(x, y));
+    // right, stopped by the wall which doesn't burn
+    assert!(on_fire(11, y) && on_fire(12, y));
+    assert!(!on_fire(13, y) && !on_fire(14, y));
+    // up, the crate burns but stops the fire
+    assert!(on_fire(x, 6) && on_fire(x, 7));
+    assert!(!on_fire(x, 8));
+    // left and down, stopped by the range
+    assert!((6..x).all(|x| on_fire(x, y)));
+    assert!(!on_fire(5, y));
+    assert!((1..y).all(|y| on_fire(x, y)));
+    assert!(!on_fire(x, 0));
+}
+
+#[test]
+fn laser_kills_player_two_columns_ahead() {
+    let mut sim = simulation();
+    let x = sim.player().x;
+
+    sim.laser.x = x + 1;
+    sim.tick(&[]);
+    assert!(sim.player().is_alive());
+
+    sim.laser.x = x + 2;
+    let events = sim.tick(&[]);
+    assert!(events.contains(&Event::PlayerDied {
+        player: 0,
+        cause: CauseOfDeath::Laser
+    }));
+    assert_eq!(sim.state(), State::Lost(CauseOfDeath::Laser));
+}
+
+#[test]
+fn players_teleport_across_edges() {
+    let mut sim = simulation();
+    let width = sim.board().width();
+    let y = sim.player().y;
+    sim.players[0].x = 0;
+
+    assert_eq!(
+        move_first_player(&mut sim, MoveDirection::Left),
+        PlayerMove::Teleport(width - 1, y)
+    );
+    assert_eq!((sim.player().x, sim.player().y), (width - 1, y));
+
+    assert_eq!(
+        move_first_player(&mut sim, MoveDirection::Right),
+        PlayerMove::Teleport(0, y)
+    );
+    assert_eq!((sim.player().x, sim.player().y), (0, y));
+
+    // can't teleport into a crate
+    sim.board.cell_mut(width - 1, y).obstacle = Some(Obstacle::new(1));
+    assert_eq!(
+        move_first_player(&mut sim, MoveDirection::Left),
+        PlayerMove::Bump(MoveDirection::Left)
+    );
+    assert_eq!(sim.player().x, 0);
+}
+
+#[test]
+fn same_seed_plays_the_same_game() {
+    let play = |seed| {
+        let mut sim = Simulation::with_mode(Config::default(), seed, Mode::Versus);
+        let bots = [Bot::new(0), Bot::new(1)];
+        let mut events = vec![];
+        while sim.is_running() && sim.time() < 60_000 {
+            let actions = bots
+                .iter()
+                .filter_map(|bot| bot.action(&sim).map(|action| (bot.player(), action)))
+                .collect::<Vec<_>>();
+            events.extend(sim.tick(&actions));
+        }
+        (
+            events,
+            format!("{:?}", sim.board()),
+            format!("{:?}", sim.players()),
+            sim.time(),
+        )
+    };
+    let game = play(7);
+    assert!(!game.0.is_empty());
+    assert_eq!(game, play(7));
+    assert_ne!(game.1, play(8).1);
+}
+
+#[test]
+fn default_config_is_valid() {
+    assert!(Config::default().validate().is_empty());
+}
+
+#[test]
+fn config_validation_reports_every_invalid_key() {
+    let config = Config {
+        board_width: 3,
+        player_powerup_chance: 1.5,
+        score_gold_medal: 0,
+        player_speed: 0,
+        laser_powerup_speed: 0.,
+        powerup_timer: -1.,
+        ..Config::default()
+    };
+    let keys: Vec<_> = config.validate().into_iter().map(|(key, _)| key).collect();
+    assert_eq!(
+        keys,
+        vec![
+            "board_width",
+            "player_powerup_chance",
+            "score_gold_medal",
+            "player_speed",
+            "laser_powerup_speed",
+            "powerup_timer",
+        ]
+    );
+}
+
+#[test]
+fn normal_difficulty_keeps_the_config() {
+    let config = Config {
+        laser_nb_obstacles: 0,
+        laser_powerup_nb_obstacles: 0,
+        ..Config::default()
+    };
+    assert_eq!(
+        serde_json::to_value(Difficulty::Normal.apply(&config)).unwrap(),
+        serde_json::to_value(&config).unwrap()
+    );
+}
+
+#[test]
+fn difficulty_presets_stay_playable() {
+    let config = Config {
+        player_nb_bombs: 1,
+        laser_nb_obstacles: 0,
+        ..Config::default()
+    };
+
+    let easy = Difficulty::Easy.apply(&config);
+    assert_eq!(easy.player_nb_bombs, 2);
+    assert_eq!(easy.laser_nb_obstacles, 0);
+    assert!(easy.laser_speed > config.laser_speed);
+
+    let insane = Difficulty::Insane.apply(&config);
+    assert_eq!(insane.player_nb_bombs, 1);
+    assert_eq!(insane.laser_nb_obstacles, 3);
+    assert!(insane.laser_speed < config.laser_speed);
+    assert!(insane.validate().is_empty());
+}
+
+#[test]
+fn replays_play_the_game_again() {
+    let mut sim = Simulation::with_mode(Config::default(), 3, Mode::Versus);
+    let bots = [Bot::new(0), Bot::new(1)];
+    while sim.is_running() && sim.time() < 30_000 {
+        if sim.time() == 10_000 {
+            sim.set_config(Config {
+                player_bomb_range: 3,
+                ..Config::default()
+            });
+        }
+        let actions = bots
+            .iter()
+            .filter_map(|bot| bot.action(&sim).map(|action| (bot.player(), action)))
+            .collect::<Vec<_>>();
+        sim.tick(&actions);
+    }
+
+    let path = std::env::temp_dir().join("kmanb-replay-test.json");
+    sim.replay().save(&path).unwrap();
+    let replay = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(replay.actions, sim.replay().actions);
+    assert_eq!(replay.config_changes.len(), 1);
+
+    let mut replayed = Simulation::from_replay(&replay);
+    while replayed.is_running() && replayed.time() < sim.time() {
+        replayed.tick(&[]);
+    }
+    assert_eq!(replayed.time(), sim.time());
+    assert_eq!(
+        format!("{:?}", replayed.board()),
+        format!("{:?}", sim.board())
+    );
+    assert_eq!(
+        format!("{:?}", replayed.players()),
+        format!("{:?}", sim.players())
+    );
+    assert_eq!(replayed.config().player_bomb_range, 3);
+}
+
+#[test]
+fn replays_from_another_version_are_rejected() {
+    let path = std::env::temp_dir().join("kmanb-old-replay-test.json");
+    Replay {
+        version: REPLAY_VERSION - 1,
+        ..simulation().replay()
+    }
+    .save(&path)
+    .unwrap();
+    let loaded = Replay::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(loaded.is_err());
+}
+
+#[test]
+fn arenas_are_parsed_from_the_top_row() {
+    let arena = Arena::parse(
+        "// a comment
+        name = Corridors
+        ..3...#..
+        |..A...#..|
+        ..B..2...",
+    )
+    .unwrap();
+    assert_eq!(arena.name, "Corridors");
+    assert_eq!((arena.width, arena.height), (9, 3));
+    assert_eq!(arena.walls, vec![(6, 2), (6, 1)]);
+    assert_eq!(
+        arena.crates,
+        vec![
+            ArenaCrate {
+                x: 2,
+                y: 2,
+                strength: 3
+            },
+            ArenaCrate {
+                x: 5,
+                y: 0,
+                strength: 2
+            },
+        ]
+    );
+    assert_eq!(arena.starts, vec![(2, 1), (2, 0)]);
+    assert_eq!(arena.blocked_lanes, vec![1]);
+    assert_eq!(Arena::parse(&arena.to_string()).unwrap(), arena);
+}
+
+#[test]
+fn invalid_arenas_are_rejected() {
+    for text in &["...", "....\n...", "..x.", "A..A", "..B.", "size = 4\n...."] {
+        assert!(Arena::parse(text).is_err(), "{:?} should not parse", text);
+    }
+}
+
+#[test]
+fn players_dont_start_on_walls_or_crates() {
+    let mut arena = Arena::empty(21, 10);
+    let usual = arena.start(0, 1);
+    arena.walls.push(usual);
+    arena.crates.push(ArenaCrate {
+        x: usual.0 + 1,
+        y: usual.1,
+        strength: 1,
+    });
+    let start = arena.start(0, 1);
+    assert_ne!(start, usual);
+    assert_eq!(
+        (start.0 as isize - usual.0 as isize).abs() + (start.1 as isize - usual.1 as isize).abs(),
+        1
+    );
+
+    // on a single row, both players would start on the same cell
+    let arena = Arena::empty(8, 1);
+    assert_ne!(arena.start(0, 2), arena.start(1, 2));
+}
+
+#[test]
+fn obstacle_kinds_unlock_with_rounds() {
+    let weights = ObstacleWeights::default();
+    assert!(weights.weight(ObstacleKind::Crate, 1) > 0.);
+    assert!(weights.weight(ObstacleKind::Treasure, 1) > 0.);
+    for kind in &[
+        ObstacleKind::Barrel,
+        ObstacleKind::Metal,
+        ObstacleKind::Regenerating,
+    ] {
+        assert_eq!(weights.weight(*kind, 1), 0.);
+        assert!(weights.weight(*kind, 10) > 0.);
+        assert!(weights.weight(*kind, 100) <= 0.3);
+    }
+}
+
+#[test]
+fn obstacles_spawn_following_their_weights() {
+    let only = |values: Vec<f64>| Curve {
+        values,
+        ..Curve::default()
+    };
+    let mut sim = Simulation::new(
+        Config {
+            obstacle_weights: ObstacleWeights {
+                crates: only(vec![0.]),
+                barrels: only(vec![0.]),
+                metal: only(vec![1.]),
+                regenerating: only(vec![0.]),
+                treasure: only(vec![0.]),
+            },
+            laser_spawn_obstacles_delay: 100,
+            ..Config::default()
+        },
+        0,
+    );
+    while sim.time() < 1000 {
+        sim.tick(&[]);
+    }
+    let kinds = sim
+        .board()
+        .positions()
+        .filter_map(|(x, y)| sim.board().cell(x, y).obstacle.as_ref())
+        .map(|obstacle| obstacle.kind)
+        .collect::<Vec<_>>();
+    assert!(!kinds.is_empty());
+    assert!(kinds.iter().all(|kind| *kind == ObstacleKind::Metal));
+}
+
+#[test]
+fn barrels_set_off_by_the_laser_destroy_metal() {
+    let mut sim = simulation();
+    let (x, y) = (10, 5);
+    sim.board.cell_mut(x, y).obstacle = Some(Obstacle::with_kind(ObstacleKind::Barrel, 1));
+    sim.board.cell_mut(x + 1, y).obstacle = Some(Obstacle::with_kind(ObstacleKind::Metal, 1));
+    sim.board.cell_mut(x, y + 3).obstacle = Some(Obstacle::with_kind(ObstacleKind::Metal, 1));
+    let laser_fire = || Fire {
+        damage: 1,
+        timer: Timer::from_millis(1, false),
+        source: FireSource::Laser,
+    };
+    sim.board.cell_mut(x, y).fire = Some(laser_fire());
+    sim.board.cell_mut(x, y + 3).fire = Some(laser_fire());
+
+    let mut stats = GameStats::default();
+    let mut destroyed = vec![];
+    while sim.time() < 1000 {
+        for event in sim.tick(&[]) {
+            stats.record(&event, &sim);
+            if let Event::ObstacleDestroyed { x, y, source, kind } = event {
+                destroyed.push((x, y, source, kind));
+            }
+        }
+    }
+    assert_eq!(
+        destroyed,
+        vec![
+            (x, y, FireSource::Laser, ObstacleKind::Barrel),
+            (x + 1, y, FireSource::Barrel(None), ObstacleKind::Metal),
+        ]
+    );
+    // the laser fire itself doesn't damage metal
+    assert!(sim.board().cell(x, y + 3).obstacle.is_some());
+    assert_eq!(stats.obstacle_destroyed_by_laser, 1);
+    assert_eq!(stats.obstacle_destroyed_by_barrel, 1);
+}
+
+#[test]
+fn remote_bombs_wait_for_the_detonate_action() {
+    let mut sim = simulation();
+    sim.players[0].remote = true;
+    sim.players[0].nb_bombs = 2;
+    let (x, y) = (sim.player().x, sim.player().y);
+
+    sim.tick(&[(0, PlayerAction::PoseBomb)]);
+    move_first_player(&mut sim, MoveDirection::Up);
+    move_first_player(&mut sim, MoveDirection::Up);
+    // posing again uses the remaining bomb instead of setting off the first one
+    sim.tick(&[(0, PlayerAction::PoseBomb)]);
+    assert!(sim.board().cell(x, y).bomb.is_some());
+    assert!(sim.board().cell(x, y + 2).bomb.is_some());
+    assert_eq!(sim.bombs_available(0), 0);
+
+    for _ in 0..1000 {
+        sim.tick(&[]);
+    }
+    assert!(sim.has_waiting_bombs(0));
+
+    let events = sim.tick(&[(0, PlayerAction::Detonate)]);
+    let exploded = events
+        .iter()
+        .filter(|event| matches!(event, Event::BombExploded { player: 0, .. }))
+        .count();
+    assert_eq!(exploded, 2);
+    assert!(!sim.has_waiting_bombs(0));
+}
+
+#[test]
+fn journal_entries_are_noted_when_they_happen() {
+    let mut sim = simulation();
+    sim.start_journal();
+    let (x, y) = (sim.player().x, sim.player().y);
+    sim.step(0.5, &[(0, PlayerAction::PoseBomb)]);
+    assert_eq!(sim.time(), 500);
+
+    let journal = sim.take_journal();
+    assert_eq!(journal[0].time, 0);
+    assert!(matches!(journal[0].event, JournalEvent::GameStarted { .. }));
+    let placed = journal
+        .iter()
+        .find(|entry| matches!(entry.event, JournalEvent::BombPlaced { .. }))
+        .unwrap();
+    assert_eq!(placed.time, TICK_MS);
+    assert_eq!(placed.event, JournalEvent::BombPlaced { player: 0, x, y });
+    assert!(sim.take_journal().is_empty());
+}
+
+#[test]
+fn game_over_reports_the_first_player_down() {
+    let mut sim = Simulation::with_mode(Config::default(), 0, Mode::Coop);
+    sim.kill(1, CauseOfDeath::BombFire);
+    sim.tick(&[]);
+    sim.tick(&[]);
+    assert!(sim.is_running());
+    sim.kill(0, CauseOfDeath::Laser);
+    let events = sim.tick(&[]);
+
+    assert_eq!(sim.state(), State::Lost(CauseOfDeath::BombFire));
+    assert!(events.contains(&Event::Lost(CauseOfDeath::BombFire)));
+}
@@ -0,0 +1,51 @@
+/// A timer counting in milliseconds of simulated time.
+#[derive(Debug, Clone)]
+pub struct Timer {
+    pub duration: u64,
+    pub elapsed: u64,
+    repeating: bool,
+    finished: bool,
+}
+
+impl Timer {
+    pub fn from_millis(duration: u64, repeating: bool) -> Self {
+        Timer {
+            duration,
+            elapsed: 0,
+            repeating,
+            finished: false,
+        }
+    }
+
+    /// Advance the timer, returns `true` if it finished during this tick.
+    pub fn tick(&mut self, delta: u64) -> bool {
+        if self.finished {
+            return false;
+        }
+        self.elapsed += delta;
+        if self.elapsed >= self.duration {
+            if self.repeating {
+                self.elapsed = self.elapsed.checked_rem(self.duration).unwrap_or(0);
+            } else {
+                self.elapsed = self.duration;
+                self.finished = true;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+        self.finished = false;
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.duration.saturating_sub(self.elapsed)
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use simulation::PlayerPowerUp;
 use tracing::info;
 
 const CURRENT_SCREEN: crate::Screen = crate::Screen::About;
@@ -323,206 +324,62 @@ fn setup(
                         ..Default::default()
                     })
                     .with_children(|controls_parent| {
-                        controls_parent
-                            .spawn(NodeComponents {
-                                style: Style {
-                                    flex_direction: FlexDirection::Row,
-                                    ..Default::default()
-                                },
-                                material: color_none.clone(),
-                                ..Default::default()
-                            })
-                            .with_children(|this_bonus_parent| {
-                                this_bonus_parent.spawn(ImageComponents {
-                                    style: Style {
-                                        size: Size {
-                                            height: Val::Px(30.),
-                                            width: Val::Px(30.),
-                                        },
-                                        ..Default::default()
-                                    },
-                                    material: board_assets.clone().powerup_score,
-                                    ..Default::default()
-                                });
-                                this_bonus_parent.spawn(TextComponents {
-                                    style: Style {
-                                        size: Size {
-                                            height: Val::Px(60. / 2.),
-                                            ..Default::default()
-                                        },
-                                        ..Default::default()
-                                    },
-                                    text: Text {
-                                        value: "Score bonus".to_string(),
-                                        font: font_sub.clone(),
-                                        style: TextStyle {
-                                            color: crate::ui::ColorScheme::TEXT,
-                                            font_size: 60.0 / 2.,
-                                        },
-                                    },
-                                    ..Default::default()
-                                });
-                            });
-                        controls_parent
-                            .spawn(NodeComponents {
-                                style: Style {
-                                    flex_direction: FlexDirection::Row,
-                                    ..Default::default()
-                                },
-                                material: color_none.clone(),
-                                ..Default::default()
-                            })
-                            .with_children(|this_bonus_parent| {
-                                this_bonus_parent.spawn(ImageComponents {
-                                    style: Style {
-                                        size: Size {
-                                            height: Val::Px(30.),
-                                            width: Val::Px(30.),
-                                        },
-                                        ..Default::default()
-                                    },
-                                    material: board_assets.clone().powerup_bomb_damage,
-                                    ..Default::default()
-                                });
-                                this_bonus_parent.spawn(TextComponents {
-                                    style: Style {
-                                        size: Size {
-                                            height: Val::Px(60. / 2.),
-                                            ..Default::default()
-                                        },
-                                        ..Default::default()
-                                    },
-                                    text: Text {
-                                        value: "Increase bomb damage".to_string(),
-                                        font: font_sub.clone(),
-                                        style: TextStyle {
-                                            color: crate::ui::ColorScheme::TEXT,
-                                            font_size: 60.0 / 2.,
-                                        },
-                                    },
-                                    ..Default::default()
-                                });
-                            });
-                        controls_parent
-                            .spawn(NodeComponents {
-                                style: Style {
-                                    flex_direction: FlexDirection::Row,
-                                    ..Default::default()
-                                },
-                                material: color_none.clone(),
-                                ..Default::default()
-                            })
-                            .with_children(|this_bonus_parent| {
-                                this_bonus_parent.spawn(ImageComponents {
+                        for (powerup, description) in &[
+                            (PlayerPowerUp::Score, "Score bonus"),
+                            (PlayerPowerUp::BombDamage, "Increase bomb damage"),
+                            (PlayerPowerUp::BombRange, "Increase bomb range"),
+                            (PlayerPowerUp::BombCount, "Increase number of bombs"),
+                            (
+                                PlayerPowerUp::BombSpeed,
+                                "Decrease delay before bomb explosion",
+                            ),
+                            (PlayerPowerUp::Kick, "Kick bombs by walking into them"),
+                            (PlayerPowerUp::Remote, "Set bombs off with the detonate key"),
+                            (PlayerPowerUp::Shield, "Survive one fire"),
+                            (PlayerPowerUp::PlayerSpeed, "Move faster"),
+                            (PlayerPowerUp::Pierce, "Bomb fire goes through obstacles"),
+                        ] {
+                            controls_parent
+                                .spawn(NodeComponents {
                                     style: Style {
-                                        size: Size {
-                                            height: Val::Px(30.),
-                                            width: Val::Px(30.),
-                                        },
+                                        flex_direction: FlexDirection::Row,
                                         ..Default::default()
                                     },
-                                    material: board_assets.clone().powerup_bomb_range,
+                                    material: color_none.clone(),
                                     ..Default::default()
-                                });
-                                this_bonus_parent.spawn(TextComponents {
-                                    style: Style {
-                                        size: Size {
-                                            height: Val::Px(60. / 2.),
+                                })
+                                .with_children(|this_bonus_parent| {
+                                    this_bonus_parent.spawn(ImageComponents {
+                                        style: Style {
+                                            size: Size {
+                                                height: Val::Px(30.),
+                                                width: Val::Px(30.),
+                                            },
                                             ..Default::default()
                                         },
+                                        material: board_assets.powerup(*powerup),
                                         ..Default::default()
-                                    },
-                                    text: Text {
-                                        value: "Increase bomb range".to_string(),
-                                        font: font_sub.clone(),
-                                        style: TextStyle {
-                                            color: crate::ui::ColorScheme::TEXT,
-                                            font_size: 60.0 / 2.,
-                                        },
-                                    },
-                                    ..Default::default()
-                                });
-                            });
-                        controls_parent
-                            .spawn(NodeComponents {
-                                style: Style {
-                                    flex_direction: FlexDirection::Row,
-                                    ..Default::default()
-                                },
-                                material: color_none.clone(),
-                                ..Default::default()
-                            })
-                            .with_children(|this_bonus_parent| {
-                                this_bonus_parent.spawn(ImageComponents {
-                                    style: Style {
-                                        size: Size {
-                                            height: Val::Px(30.),
-                                            width: Val::Px(30.),
-                                        },
-                                        ..Default::default()
-                                    },
-                                    material: board_assets.clone().powerup_bomb_count,
-                                    ..Default::default()
-                                });
-                                this_bonus_parent.spawn(TextComponents {
-                                    style: Style {
-                                        size: Size {
-                                            height: Val::Px(60. / 2.),
+                                    });
+                                    this_bonus_parent.spawn(TextComponents {
+                                        style: Style {
+                                            size: Size {
+                                                height: Val::Px(60. / 2.),
+                                                ..Default::default()
+                                            },
                                             ..Default::default()
                                         },
-                                        ..Default::default()
-                                    },
-                                    text: Text {
-                                        value: "Increase number of bombs".to_string(),
-                                        font: font_sub.clone(),
-                                        style: TextStyle {
-                                            color: crate::ui::ColorScheme::TEXT,
-                                            font_size: 60.0 / 2.,
-                                        },
-                                    },
-                                    ..Default::default()
-                                });
-                            });
-                        controls_parent
-                            .spawn(NodeComponents {
-                                style: Style {
-                                    flex_direction: FlexDirection::Row,
-                                    ..Default::default()
-                                },
-                                material: color_none.clone(),
-                                ..Default::default()
-                            })
-                            .with_children(|this_bonus_parent| {
-                                this_bonus_parent.spawn(ImageComponents {
-                                    style: Style {
-                                        size: Size {
-                                            height: Val::Px(30.),
-                                            width: Val::Px(30.),
+                                        text: Text {
+                                            value: description.to_string(),
+                                            font: font_sub.clone(),
+                                            style: TextStyle {
+                                                color: crate::ui::ColorScheme::TEXT,
+                                                font_size: 60.0 / 2.,
+                                            },
                                         },
                                         ..Default::default()
-                                    },
-                                    material: board_assets.clone().powerup_bomb_speed,
-                                    ..Default::default()
-                                });
-                                this_bonus_parent.spawn(TextComponents {
-                                    style: Style {
-                                        size: Size {
-                                            height: Val::Px(60. / 2.),
-                                            ..Default::default()
-                                        },
-                                        ..Default::default()
-                                    },
-                                    text: Text {
-                                        value: "Decrease delay before bomb explosion".to_string(),
-                                        font: font_sub.clone(),
-                                        style: TextStyle {
-                                            color: crate::ui::ColorScheme::TEXT,
-                                            font_size: 60.0 / 2.,
-                                        },
-                                    },
-                                    ..Default::default()
+                                    });
                                 });
-                            });
+                        }
                     });
             });
 
@@ -551,14 +408,18 @@ fn input_system(
     mut game_screen: ResMut<crate::GameScreen>,
     screen: Res<Screen>,
     keyboard_input: Res<Input<KeyCode>>,
+    bindings: Res<crate::bindings::Bindings>,
+    gamepad: Res<crate::gamepad::GamepadActions>,
     mouse_button_input: Res<Input<MouseButton>>,
 ) {
+    use crate::bindings::Action;
+    let triggered =
+        |action| bindings.just_released(&keyboard_input, action) || gamepad.just_pressed(action);
     if game_screen.current_screen == CURRENT_SCREEN
         && screen.loaded
         && (mouse_button_input.just_pressed(MouseButton::Left)
-            || keyboard_input.just_released(KeyCode::Escape)
-            || keyboard_input.just_released(KeyCode::Space)
-            || keyboard_input.just_released(KeyCode::Return))
+            || triggered(Action::Back)
+            || triggered(Action::Confirm))
     {
         game_screen.current_screen = crate::Screen::Menu;
     }
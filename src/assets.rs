@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{audio::AudioSource, prelude::*};
 
 macro_rules! load {
     ($assets:ident, $path:expr) => {
@@ -32,6 +32,7 @@ pub struct AssetHandles {
     board: Option<GameBoardHandles>,
     emotes: Option<EmoteHandles>,
     medals: Option<MedalHandles>,
+    audio: Option<AudioHandles>,
 }
 
 #[derive(Clone)]
@@ -49,11 +50,16 @@ pub struct GameBoardHandles {
     pub corner_bottom_right: Handle<ColorMaterial>,
     pub water: Handle<ColorMaterial>,
     pub grass: Handle<ColorMaterial>,
+    pub wall: Handle<ColorMaterial>,
     pub laser: Handle<ColorMaterial>,
     pub obstacle_100: Handle<ColorMaterial>,
     pub obstacle_75: Handle<ColorMaterial>,
     pub obstacle_50: Handle<ColorMaterial>,
     pub obstacle_25: Handle<ColorMaterial>,
+    pub obstacle_barrel: Handle<ColorMaterial>,
+    pub obstacle_metal: Handle<ColorMaterial>,
+    pub obstacle_regenerating: Handle<ColorMaterial>,
+    pub obstacle_treasure: Handle<ColorMaterial>,
     pub bomb: Handle<ColorMaterial>,
     pub bomb_icon: Handle<ColorMaterial>,
     pub fire: Handle<ColorMaterial>,
@@ -62,11 +68,34 @@ pub struct GameBoardHandles {
     pub powerup_bomb_range: Handle<ColorMaterial>,
     pub powerup_bomb_damage: Handle<ColorMaterial>,
     pub powerup_bomb_speed: Handle<ColorMaterial>,
+    pub powerup_kick: Handle<ColorMaterial>,
+    pub powerup_remote: Handle<ColorMaterial>,
+    pub powerup_shield: Handle<ColorMaterial>,
+    pub powerup_player_speed: Handle<ColorMaterial>,
+    pub powerup_pierce: Handle<ColorMaterial>,
     pub arrow_left: Handle<ColorMaterial>,
     pub arrow_right: Handle<ColorMaterial>,
     pub star: Handle<ColorMaterial>,
 }
 
+impl GameBoardHandles {
+    pub fn powerup(&self, powerup: simulation::PlayerPowerUp) -> Handle<ColorMaterial> {
+        use simulation::PlayerPowerUp;
+        match powerup {
+            PlayerPowerUp::Score => self.powerup_score.clone(),
+            PlayerPowerUp::BombCount => self.powerup_bomb_count.clone(),
+            PlayerPowerUp::BombDamage => self.powerup_bomb_damage.clone(),
+            PlayerPowerUp::BombRange => self.powerup_bomb_range.clone(),
+            PlayerPowerUp::BombSpeed => self.powerup_bomb_speed.clone(),
+            PlayerPowerUp::Kick => self.powerup_kick.clone(),
+            PlayerPowerUp::Remote => self.powerup_remote.clone(),
+            PlayerPowerUp::Shield => self.powerup_shield.clone(),
+            PlayerPowerUp::PlayerSpeed => self.powerup_player_speed.clone(),
+            PlayerPowerUp::Pierce => self.powerup_pierce.clone(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct EmoteHandles {
     pub alert: Handle<ColorMaterial>,
@@ -93,6 +122,35 @@ pub struct MedalHandles {
     pub gold: Handle<ColorMaterial>,
 }
 
+#[derive(Clone)]
+pub struct AudioHandles {
+    pub bomb_placed: Handle<AudioSource>,
+    pub explosion: Handle<AudioSource>,
+    pub chain_detonation: Handle<AudioSource>,
+    pub crate_break: Handle<AudioSource>,
+    pub powerup: Handle<AudioSource>,
+    pub teleport: Handle<AudioSource>,
+    pub death: Handle<AudioSource>,
+    pub new_round: Handle<AudioSource>,
+    pub music_menu: Handle<AudioSource>,
+    pub music_game: Handle<AudioSource>,
+}
+
+impl MedalHandles {
+    pub fn for_score(
+        &self,
+        score: u32,
+        config: &simulation::Config,
+    ) -> Option<Handle<ColorMaterial>> {
+        match score {
+            x if x < config.score_bronze_medal => None,
+            x if x < config.score_silver_medal => Some(self.bronze.clone()),
+            x if x < config.score_gold_medal => Some(self.silver.clone()),
+            _ => Some(self.gold.clone()),
+        }
+    }
+}
+
 impl AssetHandles {
     pub fn get_panel_handle(
         &mut self,
@@ -183,6 +241,14 @@ impl AssetHandles {
             let red_2 = Color::rgb(1., 0.2, 0.2);
             let red_3 = Color::rgb(1., 0., 0.);
             let yellow = crate::ui::ColorScheme::TEXT_HIGHLIGHT;
+            let grey = Color::rgb(0.55, 0.55, 0.6);
+            let orange = Color::rgb(1., 0.5, 0.);
+            let steel = Color::rgb(0.5, 0.6, 0.75);
+            let green = Color::rgb(0.5, 1., 0.5);
+            let gold = Color::rgb(1., 0.85, 0.2);
+            let blue = Color::rgb(0.4, 0.6, 1.);
+            let cyan = Color::rgb(0.4, 0.9, 1.);
+            let purple = Color::rgb(0.7, 0.4, 1.);
 
             self.board = Some(GameBoardHandles {
                 ground: colormaterial!(mats, assets, "game/rpgTile024.png"),
@@ -198,11 +264,16 @@ impl AssetHandles {
                 corner_top_right: colormaterial!(mats, assets, "game/rpgTile007.png"),
                 water: colormaterial!(mats, assets, "game/rpgTile029.png"),
                 grass: colormaterial!(mats, assets, "game/rpgTile019.png"),
+                wall: colormaterial!(mats, assets, "game/rpgTile163.png", grey),
                 laser: colormaterial!(mats, assets, "game/spark_06.png", red_fire),
                 obstacle_100: colormaterial!(mats, assets, "game/crate_02.png", red_0),
                 obstacle_75: colormaterial!(mats, assets, "game/crate_02.png", red_1),
                 obstacle_50: colormaterial!(mats, assets, "game/crate_02.png", red_2),
                 obstacle_25: colormaterial!(mats, assets, "game/crate_02.png", red_3),
+                obstacle_barrel: colormaterial!(mats, assets, "game/crate_02.png", orange),
+                obstacle_metal: colormaterial!(mats, assets, "game/crate_02.png", steel),
+                obstacle_regenerating: colormaterial!(mats, assets, "game/crate_02.png", green),
+                obstacle_treasure: colormaterial!(mats, assets, "game/crate_02.png", gold),
                 bomb: colormaterial!(mats, assets, "game/bomb.png"),
                 bomb_icon: colormaterial!(mats, assets, "game/bomb.png", red_fire),
                 fire: colormaterial!(mats, assets, "game/fire_01.png", red_fire),
@@ -211,6 +282,11 @@ impl AssetHandles {
                 powerup_bomb_damage: colormaterial!(mats, assets, "game/gemRed.png"),
                 powerup_bomb_range: colormaterial!(mats, assets, "game/gemGreen.png"),
                 powerup_bomb_speed: colormaterial!(mats, assets, "game/gemYellow.png"),
+                powerup_kick: colormaterial!(mats, assets, "game/bomb.png", blue),
+                powerup_remote: colormaterial!(mats, assets, "game/spark_06.png", yellow),
+                powerup_shield: colormaterial!(mats, assets, "game/shield.png", cyan),
+                powerup_player_speed: colormaterial!(mats, assets, "game/arrowRight.png", green),
+                powerup_pierce: colormaterial!(mats, assets, "game/fire_01.png", purple),
                 arrow_left: colormaterial!(mats, assets, "game/arrowLeft.png"),
                 arrow_right: colormaterial!(mats, assets, "game/arrowRight.png"),
                 star: colormaterial!(mats, assets, "game/star.png", yellow),
@@ -268,4 +344,22 @@ impl AssetHandles {
         }
         self.medals.as_ref().unwrap().clone()
     }
+
+    pub fn get_audio_handles(&mut self, assets: &AssetServer) -> AudioHandles {
+        if self.audio.is_none() {
+            self.audio = Some(AudioHandles {
+                bomb_placed: load!(assets, "audio/bomb_placed.wav"),
+                explosion: load!(assets, "audio/explosion.wav"),
+                chain_detonation: load!(assets, "audio/chain_detonation.wav"),
+                crate_break: load!(assets, "audio/crate_break.wav"),
+                powerup: load!(assets, "audio/powerup.wav"),
+                teleport: load!(assets, "audio/teleport.wav"),
+                death: load!(assets, "audio/death.wav"),
+                new_round: load!(assets, "audio/new_round.wav"),
+                music_menu: load!(assets, "audio/music_menu.wav"),
+                music_game: load!(assets, "audio/music_game.wav"),
+            });
+        }
+        self.audio.as_ref().unwrap().clone()
+    }
 }
@@ -0,0 +1,197 @@
+use std::io::Cursor;
+
+use bevy::{audio::AudioSource, prelude::*};
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use tracing::warn;
+
+use crate::game::{GameEvents, InterestingEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    BombPlaced,
+    Explosion,
+    ChainDetonation,
+    CrateBreak,
+    PowerUp,
+    Teleport,
+    Death,
+    NewRound,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Music {
+    Menu,
+    Game,
+}
+
+impl crate::assets::AudioHandles {
+    fn sound(&self, sound: Sound) -> &Handle<AudioSource> {
+        match sound {
+            Sound::BombPlaced => &self.bomb_placed,
+            Sound::Explosion => &self.explosion,
+            Sound::ChainDetonation => &self.chain_detonation,
+            Sound::CrateBreak => &self.crate_break,
+            Sound::PowerUp => &self.powerup,
+            Sound::Teleport => &self.teleport,
+            Sound::Death => &self.death,
+            Sound::NewRound => &self.new_round,
+        }
+    }
+
+    fn music(&self, music: Music) -> &Handle<AudioSource> {
+        match music {
+            Music::Menu => &self.music_menu,
+            Music::Game => &self.music_game,
+        }
+    }
+}
+
+/// Sounds to play at the end of the frame, each one only once even if triggered several times
+#[derive(Default)]
+pub struct SoundQueue(Vec<Sound>);
+
+impl SoundQueue {
+    pub fn push(&mut self, sound: Sound) {
+        if !self.0.contains(&sound) {
+            self.0.push(sound);
+        }
+    }
+}
+
+/// The audio device, which has to stay on the main thread
+struct AudioOutput {
+    stream: Option<(OutputStream, OutputStreamHandle)>,
+    music: Option<(Music, Sink)>,
+}
+
+impl AudioOutput {
+    fn new() -> Self {
+        let stream = match OutputStream::try_default() {
+            Ok(stream) => Some(stream),
+            Err(err) => {
+                warn!("no audio output available: {}", err);
+                None
+            }
+        };
+        AudioOutput {
+            stream,
+            music: None,
+        }
+    }
+}
+
+fn sink(stream: &OutputStreamHandle, volume: f32) -> Option<Sink> {
+    match Sink::try_new(stream) {
+        Ok(sink) => {
+            sink.set_volume(volume);
+            Some(sink)
+        }
+        Err(err) => {
+            warn!("error playing audio: {}", err);
+            None
+        }
+    }
+}
+
+fn decode(source: &AudioSource) -> Option<rodio::Decoder<Cursor<AudioSource>>> {
+    rodio::Decoder::new(Cursor::new(source.clone()))
+        .map_err(|err| warn!("error decoding audio: {}", err))
+        .ok()
+}
+
+pub struct Plugin;
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_thread_local_resource(AudioOutput::new())
+            .init_resource::<SoundQueue>()
+            .add_system(queue_sounds.system())
+            .add_system_to_stage(
+                bevy::app::stage::POST_UPDATE,
+                play_audio.thread_local_system(),
+            );
+    }
+}
+
+fn queue_sounds(
+    mut queue: ResMut<SoundQueue>,
+    (mut game_event_reader, game_events): (Local<EventReader<GameEvents>>, Res<Events<GameEvents>>),
+    (mut interesting_event_reader, interesting_events): (
+        Local<EventReader<InterestingEvent>>,
+        Res<Events<InterestingEvent>>,
+    ),
+) {
+    for event in game_event_reader.iter(&game_events) {
+        match event {
+            GameEvents::NewRound => queue.push(Sound::NewRound),
+            GameEvents::Lost(..) => queue.push(Sound::Death),
+            GameEvents::PlayerBonus(_) => queue.push(Sound::PowerUp),
+            _ => (),
+        }
+    }
+    for event in interesting_event_reader.iter(&interesting_events) {
+        queue.push(match event {
+            InterestingEvent::BombPlaced(_) | InterestingEvent::BombKicked(_) => Sound::BombPlaced,
+            InterestingEvent::BombExploded(_) | InterestingEvent::BarrelExploded => {
+                Sound::Explosion
+            }
+            InterestingEvent::BombChainDetonated(_) => Sound::ChainDetonation,
+            InterestingEvent::ObstacleDestroyedByPlayer(_)
+            | InterestingEvent::ObstacleDestroyedByLaser => Sound::CrateBreak,
+            InterestingEvent::ShieldUsed(_) => Sound::PowerUp,
+            InterestingEvent::PlayerTeleported(_) => Sound::Teleport,
+        });
+    }
+}
+
+fn play_audio(_world: &mut World, resources: &mut Resources) {
+    let mut output = resources.get_thread_local_mut::<AudioOutput>().unwrap();
+    let mut queue = resources.get_mut::<SoundQueue>().unwrap();
+    let stream = match output.stream.as_ref() {
+        Some((_, stream)) => stream.clone(),
+        None => {
+            queue.0.clear();
+            return;
+        }
+    };
+    let settings = resources.get::<crate::Settings>().unwrap();
+    let game_screen = resources.get::<crate::GameScreen>().unwrap();
+    let asset_server = resources.get::<AssetServer>().unwrap();
+    let sources = resources.get::<Assets<AudioSource>>().unwrap();
+    let handles = resources
+        .get_mut::<crate::AssetHandles>()
+        .unwrap()
+        .get_audio_handles(&asset_server);
+
+    let sfx_volume = settings.volume * settings.sfx_volume;
+    for sound in queue.0.drain(..) {
+        if let Some(decoder) = sources.get(handles.sound(sound)).and_then(decode) {
+            if let Some(sink) = sink(&stream, sfx_volume) {
+                sink.append(decoder);
+                sink.detach();
+            }
+        }
+    }
+
+    let music = match game_screen.current_screen {
+        crate::Screen::Splash | crate::Screen::Exit => None,
+        crate::Screen::Game => Some(Music::Game),
+        _ => Some(Music::Menu),
+    };
+    let music_volume = settings.volume * settings.music_volume;
+    if output.music.as_ref().map(|(playing, _)| *playing) != music {
+        if let Some((_, sink)) = output.music.take() {
+            sink.stop();
+        }
+        // the music is started once it is loaded
+        output.music = music.and_then(|music| {
+            let decoder = sources.get(handles.music(music)).and_then(decode)?;
+            let sink = sink(&stream, music_volume)?;
+            sink.append(decoder.repeat_infinite());
+            Some((music, sink))
+        });
+    } else if let Some((_, sink)) = output.music.as_ref() {
+        if (sink.volume() - music_volume).abs() > f32::EPSILON {
+            sink.set_volume(music_volume);
+        }
+    }
+}
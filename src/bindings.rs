@@ -0,0 +1,396 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Bomb,
+    Detonate,
+    Pause,
+    Confirm,
+    Back,
+    Fullscreen,
+    HighScores,
+    Player2Up,
+    Player2Down,
+    Player2Left,
+    Player2Right,
+    Player2Bomb,
+    Player2Detonate,
+    EditorSave,
+    EditorLoad,
+    EditorTestPlay,
+}
+
+impl Action {
+    pub const ALL: [Action; 20] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Bomb,
+        Action::Detonate,
+        Action::Pause,
+        Action::Confirm,
+        Action::Back,
+        Action::Fullscreen,
+        Action::HighScores,
+        Action::Player2Up,
+        Action::Player2Down,
+        Action::Player2Left,
+        Action::Player2Right,
+        Action::Player2Bomb,
+        Action::Player2Detonate,
+        Action::EditorSave,
+        Action::EditorLoad,
+        Action::EditorTestPlay,
+    ];
+}
+
+/// Actions to move, pose and set off bombs of a player
+pub struct PlayerActions {
+    pub up: Action,
+    pub down: Action,
+    pub left: Action,
+    pub right: Action,
+    pub bomb: Action,
+    pub detonate: Action,
+}
+
+pub const PLAYER_ACTIONS: [PlayerActions; 2] = [
+    PlayerActions {
+        up: Action::Up,
+        down: Action::Down,
+        left: Action::Left,
+        right: Action::Right,
+        bomb: Action::Bomb,
+        detonate: Action::Detonate,
+    },
+    PlayerActions {
+        up: Action::Player2Up,
+        down: Action::Player2Down,
+        left: Action::Player2Left,
+        right: Action::Player2Right,
+        bomb: Action::Player2Bomb,
+        detonate: Action::Player2Detonate,
+    },
+];
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Up => write!(f, "up"),
+            Action::Down => write!(f, "down"),
+            Action::Left => write!(f, "left"),
+            Action::Right => write!(f, "right"),
+            Action::Bomb => write!(f, "pose a bomb"),
+            Action::Detonate => write!(f, "set off remote bombs"),
+            Action::Pause => write!(f, "pause"),
+            Action::Confirm => write!(f, "confirm"),
+            Action::Back => write!(f, "back"),
+            Action::Fullscreen => write!(f, "toggle fullscreen"),
+            Action::HighScores => write!(f, "high scores after a game"),
+            Action::Player2Up => write!(f, "player 2 up"),
+            Action::Player2Down => write!(f, "player 2 down"),
+            Action::Player2Left => write!(f, "player 2 left"),
+            Action::Player2Right => write!(f, "player 2 right"),
+            Action::Player2Bomb => write!(f, "player 2 pose a bomb"),
+            Action::Player2Detonate => write!(f, "player 2 set off remote bombs"),
+            Action::EditorSave => write!(f, "save in the arena editor"),
+            Action::EditorLoad => write!(f, "load in the arena editor"),
+            Action::EditorTestPlay => write!(f, "test play in the arena editor"),
+        }
+    }
+}
+
+/// Key bindings as written in `settings.conf`, by key name
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct BindingsSettings {
+    up: Vec<String>,
+    down: Vec<String>,
+    left: Vec<String>,
+    right: Vec<String>,
+    bomb: Vec<String>,
+    detonate: Vec<String>,
+    pause: Vec<String>,
+    confirm: Vec<String>,
+    back: Vec<String>,
+    fullscreen: Vec<String>,
+    highscores: Vec<String>,
+    player2_up: Vec<String>,
+    player2_down: Vec<String>,
+    player2_left: Vec<String>,
+    player2_right: Vec<String>,
+    player2_bomb: Vec<String>,
+    player2_detonate: Vec<String>,
+    editor_save: Vec<String>,
+    editor_load: Vec<String>,
+    editor_test_play: Vec<String>,
+}
+
+impl Default for BindingsSettings {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect();
+        BindingsSettings {
+            up: keys(&["Up"]),
+            down: keys(&["Down"]),
+            left: keys(&["Left"]),
+            right: keys(&["Right"]),
+            bomb: keys(&["Space"]),
+            detonate: keys(&["RShift"]),
+            pause: keys(&["P", "Escape"]),
+            confirm: keys(&["Return", "Space"]),
+            back: keys(&["Escape"]),
+            fullscreen: keys(&["F"]),
+            highscores: keys(&["H"]),
+            player2_up: keys(&["W"]),
+            player2_down: keys(&["S"]),
+            player2_left: keys(&["A"]),
+            player2_right: keys(&["D"]),
+            player2_bomb: keys(&["LShift"]),
+            player2_detonate: keys(&["LControl"]),
+            editor_save: keys(&["F2"]),
+            editor_load: keys(&["F3"]),
+            editor_test_play: keys(&["F5"]),
+        }
+    }
+}
+
+impl BindingsSettings {
+    fn keys(&self, action: Action) -> &Vec<String> {
+        match action {
+            Action::Up => &self.up,
+            Action::Down => &self.down,
+            Action::Left => &self.left,
+            Action::Right => &self.right,
+            Action::Bomb => &self.bomb,
+            Action::Detonate => &self.detonate,
+            Action::Pause => &self.pause,
+            Action::Confirm => &self.confirm,
+            Action::Back => &self.back,
+            Action::Fullscreen => &self.fullscreen,
+            Action::HighScores => &self.highscores,
+            Action::Player2Up => &self.player2_up,
+            Action::Player2Down => &self.player2_down,
+            Action::Player2Left => &self.player2_left,
+            Action::Player2Right => &self.player2_right,
+            Action::Player2Bomb => &self.player2_bomb,
+            Action::Player2Detonate => &self.player2_detonate,
+            Action::EditorSave => &self.editor_save,
+            Action::EditorLoad => &self.editor_load,
+            Action::EditorTestPlay => &self.editor_test_play,
+        }
+    }
+
+    fn keys_mut(&mut self, action: Action) -> &mut Vec<String> {
+        match action {
+            Action::Up => &mut self.up,
+            Action::Down => &mut self.down,
+            Action::Left => &mut self.left,
+            Action::Right => &mut self.right,
+            Action::Bomb => &mut self.bomb,
+            Action::Detonate => &mut self.detonate,
+            Action::Pause => &mut self.pause,
+            Action::Confirm => &mut self.confirm,
+            Action::Back => &mut self.back,
+            Action::Fullscreen => &mut self.fullscreen,
+            Action::HighScores => &mut self.highscores,
+            Action::Player2Up => &mut self.player2_up,
+            Action::Player2Down => &mut self.player2_down,
+            Action::Player2Left => &mut self.player2_left,
+            Action::Player2Right => &mut self.player2_right,
+            Action::Player2Bomb => &mut self.player2_bomb,
+            Action::Player2Detonate => &mut self.player2_detonate,
+            Action::EditorSave => &mut self.editor_save,
+            Action::EditorLoad => &mut self.editor_load,
+            Action::EditorTestPlay => &mut self.editor_test_play,
+        }
+    }
+}
+
+/// Physical keys for each logical action
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    keys: Vec<(Action, Vec<KeyCode>)>,
+}
+
+impl Bindings {
+    pub fn from_settings(settings: &BindingsSettings) -> Self {
+        Bindings {
+            keys: Action::ALL
+                .iter()
+                .map(|action| {
+                    let keys = settings
+                        .keys(*action)
+                        .iter()
+                        .filter_map(|name| {
+                            let key = key_from_name(name);
+                            if key.is_none() {
+                                warn!("unknown key {} for {}", name, action);
+                            }
+                            key
+                        })
+                        .collect();
+                    (*action, keys)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_settings(&self) -> BindingsSettings {
+        let mut settings = BindingsSettings::default();
+        for (action, keys) in &self.keys {
+            *settings.keys_mut(*action) = keys.iter().map(|key| key_name(*key)).collect();
+        }
+        settings
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Replace the keys for `action` by `key`
+    pub fn rebind(&mut self, action: Action, key: KeyCode) {
+        if let Some((_, keys)) = self.keys.iter_mut().find(|(bound, _)| *bound == action) {
+            *keys = vec![key];
+        }
+    }
+
+    pub fn is(&self, key: KeyCode, action: Action) -> bool {
+        self.keys(action).contains(&key)
+    }
+
+    pub fn pressed(&self, input: &Input<KeyCode>, action: Action) -> bool {
+        self.keys(action).iter().any(|key| input.pressed(*key))
+    }
+
+    pub fn just_released(&self, input: &Input<KeyCode>, action: Action) -> bool {
+        self.keys(action)
+            .iter()
+            .any(|key| input.just_released(*key))
+    }
+}
+
+pub fn describe(keys: &[KeyCode]) -> String {
+    keys.iter()
+        .map(|key| key_name(*key))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+/// Keys that can be saved in `settings.conf`
+pub fn is_supported(key: KeyCode) -> bool {
+    KEYS.contains(&key)
+}
+
+fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEYS.iter()
+        .find(|key| key_name(**key).eq_ignore_ascii_case(name))
+        .copied()
+}
+
+const KEYS: &[KeyCode] = &[
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Key0,
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Escape,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Insert,
+    KeyCode::Home,
+    KeyCode::Delete,
+    KeyCode::End,
+    KeyCode::PageDown,
+    KeyCode::PageUp,
+    KeyCode::Left,
+    KeyCode::Up,
+    KeyCode::Right,
+    KeyCode::Down,
+    KeyCode::Back,
+    KeyCode::Return,
+    KeyCode::Space,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+    KeyCode::NumpadEnter,
+    KeyCode::Apostrophe,
+    KeyCode::Backslash,
+    KeyCode::Comma,
+    KeyCode::Equals,
+    KeyCode::LAlt,
+    KeyCode::LBracket,
+    KeyCode::LControl,
+    KeyCode::LShift,
+    KeyCode::Minus,
+    KeyCode::Period,
+    KeyCode::RAlt,
+    KeyCode::RBracket,
+    KeyCode::RControl,
+    KeyCode::RShift,
+    KeyCode::Semicolon,
+    KeyCode::Slash,
+    KeyCode::Tab,
+];
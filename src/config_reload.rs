@@ -0,0 +1,76 @@
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+use tracing::{info, warn};
+
+struct ConfigWatcher {
+    timer: Timer,
+    last_modified: Option<SystemTime>,
+}
+
+fn last_modified() -> Option<SystemTime> {
+    std::fs::metadata(crate::CONFIG_FILE)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+pub struct Plugin;
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_resource(ConfigWatcher {
+            timer: Timer::from_seconds(1., true),
+            last_modified: last_modified(),
+        })
+        .add_system(reload_config.system());
+    }
+}
+
+fn changed_keys(old: &simulation::Config, new: &simulation::Config) -> Vec<String> {
+    match (serde_json::to_value(old), serde_json::to_value(new)) {
+        (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(new))) => new
+            .iter()
+            .filter(|(key, value)| old.get(*key) != Some(value))
+            .map(|(key, value)| format!("{}: {} -> {}", key, old[key], value))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn reload_config(
+    time: Res<Time>,
+    mut watcher: ResMut<ConfigWatcher>,
+    mut config: ResMut<simulation::Config>,
+    game_screen: Res<crate::GameScreen>,
+    mut game: ResMut<crate::game::Game>,
+) {
+    watcher.timer.tick(time.delta_seconds);
+    if !watcher.timer.just_finished {
+        return;
+    }
+    let modified = last_modified();
+    if modified == watcher.last_modified {
+        return;
+    }
+    watcher.last_modified = modified;
+
+    match simulation::Config::load(crate::CONFIG_FILE) {
+        Ok(new_config) => {
+            let changes = changed_keys(&config, &new_config);
+            if changes.is_empty() {
+                return;
+            }
+            info!("{} reloaded", crate::CONFIG_FILE);
+            for change in changes {
+                info!("  {}", change);
+            }
+            game.sim
+                .set_config(game_screen.difficulty.apply(&new_config));
+            *config = new_config;
+        }
+        Err(errors) => warn!(
+            "{} not reloaded, keeping the previous values:\n{}",
+            crate::CONFIG_FILE,
+            errors
+        ),
+    }
+}
@@ -0,0 +1,275 @@
+use bevy::{
+    input::{keyboard::KeyboardInput, ElementState},
+    prelude::*,
+};
+use tracing::info;
+
+use crate::bindings::{self, Action, Bindings};
+
+const CURRENT_SCREEN: crate::Screen = crate::Screen::Controls;
+
+struct ScreenTag;
+
+struct Screen {
+    loaded: bool,
+    reload: bool,
+    selected: usize,
+    waiting_for_key: bool,
+}
+impl Default for Screen {
+    fn default() -> Self {
+        Screen {
+            loaded: false,
+            reload: false,
+            selected: 0,
+            waiting_for_key: false,
+        }
+    }
+}
+
+pub struct Plugin;
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_resource(Screen::default())
+            .add_system(input_system.system())
+            .add_system(setup.system())
+            .add_system_to_stage(crate::custom_stage::TEAR_DOWN, tear_down.system());
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    game_screen: Res<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    bindings: Res<Bindings>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut asset_handles: ResMut<crate::AssetHandles>,
+) {
+    if game_screen.current_screen == CURRENT_SCREEN && !screen.loaded {
+        info!("Loading screen");
+
+        let color_none = materials.add(Color::NONE.into());
+
+        let font: Handle<Font> = asset_handles.get_font_main_handle(&asset_server);
+
+        let font_sub: Handle<Font> = asset_handles.get_font_sub_handle(&asset_server);
+
+        let hint = if screen.waiting_for_key {
+            format!("press a key for {}", Action::ALL[screen.selected])
+        } else {
+            "up and down to select, confirm to change a key".to_string()
+        };
+
+        commands
+            .spawn(NodeComponents {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect::<Val> {
+                        left: Val::Percent(20.),
+                        right: Val::Undefined,
+                        bottom: Val::Undefined,
+                        top: Val::Percent(10.),
+                    },
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..Default::default()
+                },
+                material: color_none.clone(),
+                ..Default::default()
+            })
+            .with(ScreenTag)
+            .with_children(|parent| {
+                parent.spawn(TextComponents {
+                    style: Style {
+                        size: Size {
+                            height: Val::Px(150. / 2.),
+                            ..Default::default()
+                        },
+                        margin: Rect {
+                            bottom: Val::Px(30.),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    text: Text {
+                        value: "Controls".to_string(),
+                        font,
+                        style: TextStyle {
+                            color: crate::ui::ColorScheme::TEXT,
+                            font_size: 150.0 / 2.,
+                        },
+                    },
+                    ..Default::default()
+                });
+
+                parent.spawn(TextComponents {
+                    style: Style {
+                        size: Size {
+                            height: Val::Px(20.),
+                            ..Default::default()
+                        },
+                        margin: Rect {
+                            bottom: Val::Px(30.),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    text: Text {
+                        value: hint,
+                        font: font_sub.clone(),
+                        style: TextStyle {
+                            color: crate::ui::ColorScheme::TEXT_DIM,
+                            font_size: 20.,
+                        },
+                    },
+                    ..Default::default()
+                });
+
+                for (i, action) in Action::ALL.iter().enumerate() {
+                    let color = if i == screen.selected {
+                        crate::ui::ColorScheme::TEXT_HIGHLIGHT
+                    } else {
+                        crate::ui::ColorScheme::TEXT
+                    };
+                    parent
+                        .spawn(NodeComponents {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                align_items: AlignItems::Center,
+                                margin: Rect {
+                                    bottom: Val::Px(5.),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            },
+                            material: color_none.clone(),
+                            ..Default::default()
+                        })
+                        .with_children(|row| {
+                            let columns = [
+                                (action.to_string(), 300.),
+                                (bindings::describe(bindings.keys(*action)), 400.),
+                            ];
+                            for (value, width) in columns.iter() {
+                                row.spawn(TextComponents {
+                                    style: Style {
+                                        size: Size {
+                                            width: Val::Px(*width),
+                                            height: Val::Px(25.),
+                                        },
+                                        ..Default::default()
+                                    },
+                                    text: Text {
+                                        value: value.clone(),
+                                        font: font_sub.clone(),
+                                        style: TextStyle {
+                                            color,
+                                            font_size: 25.,
+                                        },
+                                    },
+                                    ..Default::default()
+                                });
+                            }
+                        });
+                }
+
+                parent.spawn(TextComponents {
+                    style: Style {
+                        size: Size {
+                            height: Val::Px(20.),
+                            ..Default::default()
+                        },
+                        margin: Rect {
+                            top: Val::Px(30.),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    text: Text {
+                        value: "gamepad: d-pad or left stick to move, south button to pose a bomb or confirm, east button to go back, start to pause".to_string(),
+                        font: font_sub.clone(),
+                        style: TextStyle {
+                            color: crate::ui::ColorScheme::TEXT_DIM,
+                            font_size: 20.,
+                        },
+                    },
+                    ..Default::default()
+                });
+            });
+
+        screen.loaded = true;
+        screen.reload = false;
+    }
+}
+
+fn tear_down(
+    mut commands: Commands,
+    game_screen: Res<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    query: Query<With<ScreenTag, Entity>>,
+) {
+    if (game_screen.current_screen != CURRENT_SCREEN || screen.reload) && screen.loaded {
+        info!("tear down");
+
+        for entity in &mut query.iter() {
+            commands.despawn_recursive(entity);
+        }
+
+        screen.loaded = false;
+    }
+}
+
+fn input_system(
+    mut game_screen: ResMut<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    keyboard_input: Res<Input<KeyCode>>,
+    (mut event_reader, keyboard_input_events): (
+        Local<EventReader<KeyboardInput>>,
+        Res<Events<KeyboardInput>>,
+    ),
+    mut bindings: ResMut<Bindings>,
+    gamepad: Res<crate::gamepad::GamepadActions>,
+    mut settings: ResMut<crate::Settings>,
+) {
+    if game_screen.current_screen != CURRENT_SCREEN || !screen.loaded {
+        // don't pick up keys pressed on another screen once this one is shown
+        for _ in event_reader.iter(&keyboard_input_events) {}
+        return;
+    }
+
+    if screen.waiting_for_key {
+        for event in event_reader.iter(&keyboard_input_events) {
+            // wait for the release so that the key isn't also handled as an action
+            if event.state != ElementState::Released {
+                continue;
+            }
+            if let Some(key) = event.key_code.filter(|key| bindings::is_supported(*key)) {
+                let action = Action::ALL[screen.selected];
+                info!("binding {} to {}", action, bindings::key_name(key));
+                bindings.rebind(action, key);
+                settings.bindings = bindings.to_settings();
+                settings.save();
+                screen.waiting_for_key = false;
+                screen.reload = true;
+                break;
+            }
+        }
+        return;
+    }
+    for _ in event_reader.iter(&keyboard_input_events) {}
+
+    let triggered =
+        |action| bindings.just_released(&keyboard_input, action) || gamepad.just_pressed(action);
+    if triggered(Action::Down) {
+        screen.selected = usize::min(Action::ALL.len() - 1, screen.selected + 1);
+        screen.reload = true;
+    } else if triggered(Action::Up) {
+        screen.selected = screen.selected.saturating_sub(1);
+        screen.reload = true;
+    } else if triggered(Action::Confirm) {
+        screen.waiting_for_key = true;
+        screen.reload = true;
+    } else if triggered(Action::Back) {
+        game_screen.current_screen = crate::Screen::Menu;
+    }
+}
@@ -0,0 +1,726 @@
+use bevy::{prelude::*, window::WindowResized};
+use simulation::{Arena, ArenaCrate};
+use tracing::info;
+
+use crate::bindings::{Action, Bindings};
+use crate::game::{Layout, PLAYER_COLORS, PLAYER_SIZE, TILE_SIZE};
+
+const CURRENT_SCREEN: crate::Screen = crate::Screen::Editor;
+
+const ARENA_FOLDER: &str = "arenas";
+const ARENA_FILE: &str = "arenas/custom.arena";
+
+const STRENGTH_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+const Z_GROUND: f32 = 0.0;
+const Z_CONTENT: f32 = 0.1;
+const Z_CURSOR: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Tool {
+    Wall,
+    Crate,
+    Start(usize),
+    Lane,
+    Erase,
+}
+
+const TOOLS: [Tool; 6] = [
+    Tool::Wall,
+    Tool::Crate,
+    Tool::Start(0),
+    Tool::Start(1),
+    Tool::Lane,
+    Tool::Erase,
+];
+
+impl Tool {
+    fn name(self, strength: usize) -> String {
+        match self {
+            Tool::Wall => "wall".to_string(),
+            Tool::Crate => format!("crate of strength {}", strength),
+            Tool::Start(0) => "start of player A".to_string(),
+            Tool::Start(_) => "start of player B".to_string(),
+            Tool::Lane => "block teleport lane".to_string(),
+            Tool::Erase => "erase".to_string(),
+        }
+    }
+
+    fn next(self) -> Tool {
+        let current = TOOLS.iter().position(|tool| *tool == self).unwrap_or(0);
+        TOOLS[(current + 1) % TOOLS.len()]
+    }
+}
+
+struct ScreenTag;
+/// Sprites of the board, spawned again each time the arena changes
+struct BoardTag;
+struct EditorCursor;
+struct StatusText;
+
+struct Screen {
+    loaded: bool,
+    arena: Arena,
+    cursor: (usize, usize),
+    tool: Tool,
+    strength: usize,
+    layout: Layout,
+    /// the board sprites need to be spawned again
+    dirty: bool,
+    message: String,
+    /// back from a test play, keep editing the same arena
+    test_played: bool,
+}
+impl Default for Screen {
+    fn default() -> Self {
+        Screen {
+            loaded: false,
+            arena: Arena::empty(1, 1),
+            cursor: (0, 0),
+            tool: Tool::Wall,
+            strength: 1,
+            layout: Layout::default(),
+            dirty: false,
+            message: String::new(),
+            test_played: false,
+        }
+    }
+}
+
+impl Screen {
+    fn clear(&mut self, position: (usize, usize)) {
+        let arena = &mut self.arena;
+        arena.walls.retain(|wall| *wall != position);
+        arena.crates.retain(|c| (c.x, c.y) != position);
+        arena.starts.retain(|start| *start != position);
+    }
+
+    /// Use `tool` on the cell under the cursor
+    fn edit(&mut self, tool: Tool) {
+        let (x, y) = self.cursor;
+        match tool {
+            Tool::Wall => {
+                self.clear((x, y));
+                self.arena.walls.push((x, y));
+            }
+            Tool::Crate => {
+                self.clear((x, y));
+                self.arena.crates.push(ArenaCrate {
+                    x,
+                    y,
+                    strength: self.strength,
+                });
+            }
+            Tool::Start(player) if player > self.arena.starts.len() => {
+                self.message = "place the start of player A first".to_string();
+                return;
+            }
+            Tool::Start(player) => {
+                self.clear((x, y));
+                if player < self.arena.starts.len() {
+                    self.arena.starts[player] = (x, y);
+                } else {
+                    self.arena.starts.push((x, y));
+                }
+            }
+            Tool::Lane => {
+                let lanes = &mut self.arena.blocked_lanes;
+                match lanes.iter().position(|lane| *lane == y) {
+                    Some(index) => {
+                        lanes.remove(index);
+                    }
+                    None => {
+                        lanes.push(y);
+                        lanes.sort_unstable();
+                    }
+                }
+            }
+            Tool::Erase => self.clear((x, y)),
+        }
+        self.message.clear();
+        self.dirty = true;
+    }
+
+    fn save(&mut self) {
+        let saved = match std::fs::create_dir_all(ARENA_FOLDER) {
+            Ok(()) => self.arena.save(ARENA_FILE),
+            Err(err) => Err(err.into()),
+        };
+        self.message = match saved {
+            Ok(()) => format!("saved to {}", ARENA_FILE),
+            Err(err) => format!("error saving: {}", err),
+        };
+        info!("{}", self.message);
+    }
+
+    fn load(&mut self) {
+        match Arena::load(ARENA_FILE) {
+            Ok(arena) => {
+                self.arena = arena;
+                self.message = format!("loaded {}", ARENA_FILE);
+            }
+            Err(err) => self.message = format!("error loading: {}", err),
+        }
+        info!("{}", self.message);
+        self.cursor = (
+            self.cursor.0.min(self.arena.width - 1),
+            self.cursor.1.min(self.arena.height - 1),
+        );
+        self.dirty = true;
+    }
+
+    fn cell_description(&self) -> String {
+        let (x, y) = self.cursor;
+        let arena = &self.arena;
+        let content = if let Some(player) = arena.starts.iter().position(|s| *s == (x, y)) {
+            format!("start of player {}", if player == 0 { "A" } else { "B" })
+        } else if arena.walls.contains(&(x, y)) {
+            "wall".to_string()
+        } else if let Some(obstacle) = arena.crates.iter().find(|c| (c.x, c.y) == (x, y)) {
+            format!("crate of strength {}", obstacle.strength)
+        } else {
+            "empty".to_string()
+        };
+        let lane = if arena.blocked_lanes.contains(&y) {
+            ", lane blocked"
+        } else {
+            ""
+        };
+        format!("cell {}, {}: {}{}", x, y, content, lane)
+    }
+}
+
+pub struct Plugin;
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_resource(Screen::default())
+            .add_system(setup.system())
+            .add_system(input_system.system())
+            .add_system(mouse_system.system())
+            .add_system(button_system.system())
+            .add_system(display_board.system())
+            .add_system(display_cursor.system())
+            .add_system(display_status.system())
+            .add_system_to_stage(crate::custom_stage::TEAR_DOWN, tear_down.system());
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum EditorButton {
+    Save,
+    Load,
+    TestPlay,
+    Back,
+}
+
+const BUTTONS: [EditorButton; 4] = [
+    EditorButton::Save,
+    EditorButton::Load,
+    EditorButton::TestPlay,
+    EditorButton::Back,
+];
+
+impl Into<String> for EditorButton {
+    fn into(self) -> String {
+        match self {
+            EditorButton::Save => "Save".to_string(),
+            EditorButton::Load => "Load".to_string(),
+            EditorButton::TestPlay => "Test play".to_string(),
+            EditorButton::Back => "Back".to_string(),
+        }
+    }
+}
+
+impl EditorButton {
+    fn activate(
+        self,
+        game_screen: &mut crate::GameScreen,
+        screen: &mut Screen,
+        test_play: &mut crate::game::TestPlayArena,
+    ) {
+        match self {
+            EditorButton::Save => screen.save(),
+            EditorButton::Load => screen.load(),
+            EditorButton::TestPlay => {
+                test_play.0 = Some(screen.arena.clone());
+                screen.test_played = true;
+                game_screen.current_screen = crate::Screen::Game;
+            }
+            EditorButton::Back => game_screen.current_screen = crate::Screen::Menu,
+        }
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    (game_screen, mut screen, arena_mode, config, bindings): (
+        Res<crate::GameScreen>,
+        ResMut<Screen>,
+        Res<crate::game::ArenaMode>,
+        Res<simulation::Config>,
+        Res<Bindings>,
+    ),
+    asset_server: Res<AssetServer>,
+    mut asset_handles: ResMut<crate::AssetHandles>,
+    mut nine_patches: ResMut<Assets<bevy_ninepatch::NinePatchBuilder<()>>>,
+    mut buttons: ResMut<Assets<crate::ui::button::Button>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if game_screen.current_screen == CURRENT_SCREEN && !screen.loaded {
+        info!("Loading screen");
+
+        // edit the arena games are played on, or the last one saved from the editor
+        if !std::mem::take(&mut screen.test_played) {
+            screen.arena = arena_mode
+                .0
+                .clone()
+                .or_else(|| Arena::load(ARENA_FILE).ok())
+                .unwrap_or_else(|| {
+                    config
+                        .board_layout
+                        .arena(config.board_width, config.board_height)
+                });
+        }
+        screen.cursor = (screen.arena.width / 2, screen.arena.height / 2);
+        screen.message.clear();
+        screen.dirty = true;
+
+        let color_none = materials.add(Color::NONE.into());
+
+        let font: Handle<Font> = asset_handles.get_font_main_handle(&asset_server);
+        let font_sub: Handle<Font> = asset_handles.get_font_sub_handle(&asset_server);
+
+        let button_handle = asset_handles.get_button_handle(
+            &asset_server,
+            &mut materials,
+            &mut nine_patches,
+            &mut buttons,
+        );
+        let button = buttons.get(&button_handle).unwrap();
+
+        let status = commands
+            .spawn(TextComponents {
+                style: Style {
+                    size: Size {
+                        height: Val::Px(25.),
+                        ..Default::default()
+                    },
+                    margin: Rect {
+                        bottom: Val::Px(5.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text {
+                    value: "".to_string(),
+                    font: font_sub.clone(),
+                    style: TextStyle {
+                        color: crate::ui::ColorScheme::TEXT,
+                        font_size: 25.,
+                    },
+                },
+                ..Default::default()
+            })
+            .with(StatusText)
+            .current_entity()
+            .unwrap();
+        let hint = commands
+            .spawn(TextComponents {
+                style: Style {
+                    size: Size {
+                        height: Val::Px(20.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text {
+                    value: format!(
+                        "arrows or mouse to move, space or click to place, right click or \
+                        delete to erase, tab to change tool, 1 to 9 for crate strength, \
+                        {} to save, {} to load, {} to test play",
+                        crate::bindings::describe(bindings.keys(Action::EditorSave)),
+                        crate::bindings::describe(bindings.keys(Action::EditorLoad)),
+                        crate::bindings::describe(bindings.keys(Action::EditorTestPlay)),
+                    ),
+                    font: font_sub,
+                    style: TextStyle {
+                        color: crate::ui::ColorScheme::TEXT_DIM,
+                        font_size: 20.,
+                    },
+                },
+                ..Default::default()
+            })
+            .current_entity()
+            .unwrap();
+        let text = commands
+            .spawn(NodeComponents {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect::<Val> {
+                        left: Val::Px(20.),
+                        right: Val::Undefined,
+                        bottom: Val::Undefined,
+                        top: Val::Px(10.),
+                    },
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..Default::default()
+                },
+                material: color_none.clone(),
+                ..Default::default()
+            })
+            .with(ScreenTag)
+            .current_entity()
+            .unwrap();
+        commands.push_children(text, &[status, hint]);
+
+        let buttons = BUTTONS
+            .iter()
+            .map(|button_item| {
+                button.add(
+                    &mut commands,
+                    200.,
+                    40.,
+                    Rect {
+                        left: Val::Px(10.),
+                        right: Val::Px(10.),
+                        ..Default::default()
+                    },
+                    font.clone(),
+                    *button_item,
+                    20.,
+                )
+            })
+            .collect::<Vec<_>>();
+        let button_row = commands
+            .spawn(NodeComponents {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect::<Val> {
+                        left: Val::Px(20.),
+                        right: Val::Undefined,
+                        bottom: Val::Px(10.),
+                        top: Val::Undefined,
+                    },
+                    flex_direction: FlexDirection::Row,
+                    ..Default::default()
+                },
+                material: color_none,
+                ..Default::default()
+            })
+            .with(ScreenTag)
+            .current_entity()
+            .unwrap();
+        commands.push_children(button_row, buttons.as_slice());
+
+        commands
+            .spawn(SpriteComponents {
+                material: materials.add(Color::rgba(1., 1., 1., 0.3).into()),
+                sprite: Sprite::new(Vec2::new(TILE_SIZE, TILE_SIZE)),
+                ..Default::default()
+            })
+            .with(EditorCursor)
+            .with(ScreenTag);
+
+        screen.loaded = true;
+    }
+}
+
+fn tear_down(
+    mut commands: Commands,
+    game_screen: Res<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    query: Query<With<ScreenTag, Entity>>,
+    board_query: Query<With<BoardTag, Entity>>,
+) {
+    if game_screen.current_screen != CURRENT_SCREEN && screen.loaded {
+        info!("tear down");
+
+        for entity in query.iter().chain(board_query.iter()) {
+            commands.despawn_recursive(entity);
+        }
+
+        screen.loaded = false;
+    }
+}
+
+fn crate_material(
+    board_handles: &crate::assets::GameBoardHandles,
+    strength: usize,
+) -> Handle<ColorMaterial> {
+    match strength {
+        0..=2 => board_handles.obstacle_25.clone(),
+        3..=4 => board_handles.obstacle_50.clone(),
+        5..=6 => board_handles.obstacle_75.clone(),
+        _ => board_handles.obstacle_100.clone(),
+    }
+}
+
+fn display_board(
+    mut commands: Commands,
+    game_screen: Res<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    wnds: Res<Windows>,
+    (mut event_reader, events): (
+        Local<EventReader<WindowResized>>,
+        Res<Events<WindowResized>>,
+    ),
+    (mut asset_handles, asset_server, mut materials, mut texture_atlases): (
+        ResMut<crate::AssetHandles>,
+        Res<AssetServer>,
+        ResMut<Assets<ColorMaterial>>,
+        ResMut<Assets<TextureAtlas>>,
+    ),
+    board_query: Query<With<BoardTag, Entity>>,
+) {
+    let resized = event_reader.iter(&events).last().is_some();
+    if game_screen.current_screen != CURRENT_SCREEN || !screen.loaded {
+        return;
+    }
+    if !screen.dirty && !resized {
+        return;
+    }
+    for entity in board_query.iter() {
+        commands.despawn_recursive(entity);
+    }
+
+    let (width, height) = (screen.arena.width, screen.arena.height);
+    let mut layout = Layout::new(wnds.get_primary().unwrap(), width, height);
+    // keep room around the board for the text and the buttons
+    layout.ratio *= 0.75;
+    let ratio = layout.ratio;
+    screen.layout = layout;
+    screen.dirty = false;
+
+    let board_handles = asset_handles.get_board_handles(&asset_server, &mut materials);
+    let character_handle = asset_handles.get_character_handle(&asset_server, &mut texture_atlases);
+    let arena = &screen.arena;
+    let position =
+        |x: usize, y: usize, z: f32| Vec3::new(layout.x_to(x as i32), layout.y_to(y as i32), z);
+
+    for y in 0..height {
+        for x in 0..width {
+            commands
+                .spawn(SpriteComponents {
+                    material: crate::game::ground_material(&board_handles, x, y, width, height),
+                    transform: Transform {
+                        translation: position(x, y, Z_GROUND),
+                        scale: Vec3::splat(ratio),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with(BoardTag);
+        }
+    }
+    for (x, y) in &arena.walls {
+        commands
+            .spawn(SpriteComponents {
+                material: board_handles.wall.clone(),
+                transform: Transform {
+                    translation: position(*x, *y, Z_CONTENT),
+                    scale: Vec3::splat(ratio),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .with(BoardTag);
+    }
+    for obstacle in &arena.crates {
+        commands
+            .spawn(SpriteComponents {
+                material: crate_material(&board_handles, obstacle.strength),
+                transform: Transform {
+                    translation: position(obstacle.x, obstacle.y, Z_CONTENT),
+                    scale: Vec3::splat(ratio * 0.5),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .with(BoardTag);
+    }
+    for (player, (x, y)) in arena.starts.iter().enumerate() {
+        commands
+            .spawn(SpriteSheetComponents {
+                texture_atlas: character_handle.clone(),
+                transform: Transform {
+                    translation: position(*x, *y, Z_CONTENT),
+                    scale: Vec3::splat(ratio * TILE_SIZE / PLAYER_SIZE),
+                    ..Default::default()
+                },
+                sprite: TextureAtlasSprite {
+                    index: 36,
+                    color: PLAYER_COLORS[player % PLAYER_COLORS.len()],
+                },
+                ..Default::default()
+            })
+            .with(BoardTag);
+    }
+    for y in &arena.blocked_lanes {
+        for (x, side) in &[(0, -1f32), (width - 1, 1f32)] {
+            commands
+                .spawn(SpriteComponents {
+                    material: board_handles.wall.clone(),
+                    transform: Transform {
+                        translation: position(*x, *y, Z_CONTENT)
+                            + Vec3::new(side * TILE_SIZE * 3. / 8. * ratio, 0., 0.),
+                        scale: Vec3::new(ratio * 0.25, ratio, 1.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with(BoardTag);
+        }
+    }
+}
+
+fn display_cursor(screen: Res<Screen>, mut query: Query<With<EditorCursor, &mut Transform>>) {
+    let (x, y) = screen.cursor;
+    for mut transform in query.iter_mut() {
+        transform.translation = Vec3::new(
+            screen.layout.x_to(x as i32),
+            screen.layout.y_to(y as i32),
+            Z_CURSOR,
+        );
+        transform.scale = Vec3::splat(screen.layout.ratio);
+    }
+}
+
+fn display_status(screen: Res<Screen>, mut query: Query<With<StatusText, &mut Text>>) {
+    let mut status = format!(
+        "{} - tool: {}",
+        screen.cell_description(),
+        screen.tool.name(screen.strength)
+    );
+    if !screen.message.is_empty() {
+        status = format!("{} - {}", status, screen.message);
+    }
+    for mut text in query.iter_mut() {
+        if text.value != status {
+            text.value = status.clone();
+        }
+    }
+}
+
+fn input_system(
+    mut game_screen: ResMut<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    mut test_play: ResMut<crate::game::TestPlayArena>,
+    keyboard_input: Res<Input<KeyCode>>,
+    bindings: Res<Bindings>,
+    gamepad: Res<crate::gamepad::GamepadActions>,
+) {
+    if game_screen.current_screen != CURRENT_SCREEN || !screen.loaded {
+        return;
+    }
+    let triggered =
+        |action| bindings.just_released(&keyboard_input, action) || gamepad.just_pressed(action);
+    let (x, y) = screen.cursor;
+    let (width, height) = (screen.arena.width, screen.arena.height);
+    if triggered(Action::Up) {
+        screen.cursor = (x, usize::min(height - 1, y + 1));
+    } else if triggered(Action::Down) {
+        screen.cursor = (x, y.saturating_sub(1));
+    } else if triggered(Action::Left) {
+        screen.cursor = (x.saturating_sub(1), y);
+    } else if triggered(Action::Right) {
+        screen.cursor = (usize::min(width - 1, x + 1), y);
+    } else if triggered(Action::Confirm) {
+        let tool = screen.tool;
+        screen.edit(tool);
+    } else if triggered(Action::Back) {
+        EditorButton::Back.activate(&mut game_screen, &mut screen, &mut test_play);
+    } else if keyboard_input.just_released(KeyCode::Delete)
+        || keyboard_input.just_released(KeyCode::Back)
+    {
+        screen.edit(Tool::Erase);
+    } else if keyboard_input.just_released(KeyCode::Tab) {
+        screen.tool = screen.tool.next();
+    } else if triggered(Action::EditorSave) {
+        EditorButton::Save.activate(&mut game_screen, &mut screen, &mut test_play);
+    } else if triggered(Action::EditorLoad) {
+        EditorButton::Load.activate(&mut game_screen, &mut screen, &mut test_play);
+    } else if triggered(Action::EditorTestPlay) {
+        EditorButton::TestPlay.activate(&mut game_screen, &mut screen, &mut test_play);
+    } else if let Some(strength) = STRENGTH_KEYS
+        .iter()
+        .position(|key| keyboard_input.just_released(*key))
+    {
+        screen.tool = Tool::Crate;
+        screen.strength = strength + 1;
+    }
+}
+
+fn mouse_system(
+    game_screen: Res<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    wnds: Res<Windows>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    (mut event_reader, events): (Local<EventReader<CursorMoved>>, Res<Events<CursorMoved>>),
+    mut hovered: Local<Option<(usize, usize)>>,
+) {
+    let moved = event_reader.iter(&events).last();
+    if game_screen.current_screen != CURRENT_SCREEN || !screen.loaded {
+        return;
+    }
+    let mut changed_cell = false;
+    if let Some(moved) = moved {
+        let window = wnds.get(moved.id).unwrap();
+        // the camera is centered on the board
+        let position =
+            moved.position - Vec2::new(window.width() as f32 / 2., window.height() as f32 / 2.);
+        let cell = screen.layout.cell_at(position);
+        changed_cell = cell != *hovered;
+        *hovered = cell;
+    }
+    let cell = match *hovered {
+        Some(cell) => cell,
+        None => return,
+    };
+    if changed_cell {
+        screen.cursor = cell;
+    }
+    let dragged = changed_cell && screen.tool != Tool::Lane;
+    if mouse_button_input.just_pressed(MouseButton::Left)
+        || (dragged && mouse_button_input.pressed(MouseButton::Left))
+    {
+        let tool = screen.tool;
+        screen.edit(tool);
+    } else if mouse_button_input.just_pressed(MouseButton::Right)
+        || (changed_cell && mouse_button_input.pressed(MouseButton::Right))
+    {
+        screen.edit(Tool::Erase);
+    }
+}
+
+fn button_system(
+    mut game_screen: ResMut<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    mut test_play: ResMut<crate::game::TestPlayArena>,
+    mut interaction_query: Query<(
+        &Button,
+        Mutated<Interaction>,
+        &crate::ui::button::ButtonId<EditorButton>,
+    )>,
+) {
+    for (_button, interaction, button_id) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                button_id
+                    .0
+                    .activate(&mut game_screen, &mut screen, &mut test_play)
+            }
+            Interaction::Hovered => (),
+            Interaction::None => (),
+        }
+    }
+}
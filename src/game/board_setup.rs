@@ -30,36 +30,94 @@ pub fn remove_indications(
     }
 }
 
+/// Ground tile of the cell at `(x, y)` on a board of `width` by `height`
+pub(crate) fn ground_material(
+    board_handles: &crate::assets::GameBoardHandles,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+) -> Handle<ColorMaterial> {
+    match (x, y) {
+        (0, _) => board_handles.grass.clone(),
+        (x, 0) if x == width - 1 => board_handles.corner_bottom_right.clone(),
+        (1, 0) => board_handles.corner_bottom_left.clone(),
+        (1, y) if y == height - 1 => board_handles.corner_top_left.clone(),
+        (x, y) if x == width - 1 && y == height - 1 => board_handles.corner_top_right.clone(),
+        (1, _) => board_handles.ground_left.clone(),
+        (x, _) if x == width - 1 => board_handles.ground_right.clone(),
+        (_, 0) => board_handles.ground_bottom.clone(),
+        (_, y) if y == height - 1 => board_handles.ground_top.clone(),
+        _ => board_handles.ground.clone(),
+    }
+}
+
 pub fn setup(
     mut commands: Commands,
-    (game_screen, mut game, screen, mut stats): (
+    (
+        game_screen,
+        mut game,
+        screen,
+        mut stats,
+        mut rng,
+        replay_mode,
+        (arena_mode, mut test_play),
+        config,
+    ): (
         Res<crate::GameScreen>,
         ResMut<Game>,
         Res<Screen>,
-        ResMut<crate::lost::GameStats>,
+        ResMut<simulation::GameStats>,
+        ResMut<GameRng>,
+        Res<ReplayMode>,
+        (Res<ArenaMode>, ResMut<TestPlayArena>),
+        Res<simulation::Config>,
     ),
     mut asset_handles: ResMut<crate::AssetHandles>,
     (asset_server, mut materials): (Res<AssetServer>, ResMut<Assets<ColorMaterial>>),
-    wnds: Res<Windows>,
+    (wnds, mut layout): (Res<Windows>, ResMut<Layout>),
+    mut journal: ResMut<super::journal::GameJournal>,
 ) {
     if game_screen.current_screen == CURRENT_SCREEN && !screen.loaded {
         info!("Loading screen (board)");
-        *game = Game::default();
-        *stats = crate::lost::GameStats::default();
+        *game = match replay_mode.0.as_ref().filter(|_| !game_screen.demo) {
+            Some(replay) => {
+                rng.reseed(replay.seed);
+                Game::from_replay(replay)
+            }
+            None => Game::new(
+                game_screen.difficulty.apply(&config),
+                rng.new_game(),
+                game_screen.mode,
+                test_play
+                    .0
+                    .take()
+                    .or_else(|| arena_mode.0.clone())
+                    .filter(|_| !game_screen.demo),
+            ),
+        };
+        *stats = simulation::GameStats::default();
+        *journal = if game_screen.demo {
+            super::journal::GameJournal::default()
+        } else {
+            super::journal::GameJournal::start(&mut game.sim)
+        };
 
-        let ratio = wnds.get_primary().unwrap().width() as f32 / BOARD_X as f32 / TILE_SIZE as f32;
+        let (width, height) = (game.sim.board().width(), game.sim.board().height());
+        *layout = Layout::new(wnds.get_primary().unwrap(), width, height);
+        let ratio = layout.ratio;
 
         let board_handles = asset_handles.get_board_handles(&asset_server, &mut materials);
 
         if game.board.is_none() {
-            for x in 0..BOARD_X {
+            for x in 0..width {
                 commands
                     .spawn(SpriteComponents {
                         material: board_handles.border_bottom.clone(),
                         transform: Transform {
                             translation: Vec3::new(
-                                x_to(x as i32, ratio),
-                                y_to(-1, ratio),
+                                layout.x_to(x as i32),
+                                layout.y_to(-1),
                                 Z_BACKGROUND,
                             ),
                             scale: Vec3::splat(ratio),
@@ -70,31 +128,16 @@ pub fn setup(
                     .with(ScreenTag);
             }
             let mut board = vec![];
-            for y in 0..BOARD_Y {
+            for y in 0..height {
                 let mut line = vec![];
-                for x in 0..BOARD_X {
+                for x in 0..width {
                     commands
                         .spawn(SpriteComponents {
-                            material: match (x, y) {
-                                (0, _) => board_handles.grass.clone(),
-                                (x, 0) if x == BOARD_X - 1 => {
-                                    board_handles.corner_bottom_right.clone()
-                                }
-                                (1, 0) => board_handles.corner_bottom_left.clone(),
-                                (1, y) if y == BOARD_Y - 1 => board_handles.corner_top_left.clone(),
-                                (x, y) if x == BOARD_X - 1 && y == BOARD_Y - 1 => {
-                                    board_handles.corner_top_right.clone()
-                                }
-                                (1, _) => board_handles.ground_left.clone(),
-                                (x, _) if x == BOARD_X - 1 => board_handles.ground_right.clone(),
-                                (_, 0) => board_handles.ground_bottom.clone(),
-                                (_, y) if y == BOARD_Y - 1 => board_handles.ground_top.clone(),
-                                _ => board_handles.ground.clone(),
-                            },
+                            material: ground_material(&board_handles, x, y, width, height),
                             transform: Transform {
                                 translation: Vec3::new(
-                                    x_to(x as i32, ratio),
-                                    y_to(y as i32, ratio),
+                                    layout.x_to(x as i32),
+                                    layout.y_to(y as i32),
                                     Z_BACKGROUND,
                                 ),
                                 scale: Vec3::splat(ratio),
@@ -105,7 +148,35 @@ pub fn setup(
                         })
                         .with(ScreenTag);
                     let entity = commands.current_entity().unwrap();
-                    if x == 0 || x == BOARD_X - 1 {
+                    if game.sim.board().cell(x, y).wall {
+                        commands.with_children(|cell| {
+                            cell.spawn(SpriteComponents {
+                                material: board_handles.wall.clone(),
+                                transform: Transform {
+                                    translation: Vec3::new(0., 0., Z_OBSTACLE),
+                                    scale: Vec3::splat(ratio),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            });
+                        });
+                    }
+                    let edge = x == 0 || x == width - 1;
+                    if edge && !game.sim.board().can_teleport(y) {
+                        // a fence on the side of the board where players can't teleport
+                        let side = if x == 0 { -1. } else { 1. };
+                        commands.with_children(|cell| {
+                            cell.spawn(SpriteComponents {
+                                material: board_handles.wall.clone(),
+                                transform: Transform {
+                                    translation: Vec3::new(side * 24., 0., Z_OBSTACLE),
+                                    scale: Vec3::new(ratio * 0.25, ratio, 1.),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            });
+                        });
+                    } else if edge {
                         commands.with(TeleportIndicationComponent {
                             timer: Timer::from_seconds(5., false),
                         });
@@ -144,19 +215,19 @@ pub fn setup(
                             .with(TeleportIndicationSprite);
                         });
                     }
-                    line.push(Cell { entity });
+                    line.push(Cell::new(entity));
                 }
                 board.push(line);
             }
             game.board = Some(board);
-            for x in 0..BOARD_X {
+            for x in 0..width {
                 commands
                     .spawn(SpriteComponents {
                         material: board_handles.border_top.clone(),
                         transform: Transform {
                             translation: Vec3::new(
-                                x_to(x as i32, ratio),
-                                y_to(BOARD_Y as i32, ratio),
+                                layout.x_to(x as i32),
+                                layout.y_to(height as i32),
                                 Z_BACKGROUND,
                             ),
                             scale: Vec3::splat(ratio),
@@ -166,15 +237,15 @@ pub fn setup(
                     })
                     .with(ScreenTag);
             }
-            for y in (BOARD_Y + 1)..(BOARD_Y + 3) {
-                for x in 0..BOARD_X {
+            for y in (height + 1)..(height + 3) {
+                for x in 0..width {
                     commands
                         .spawn(SpriteComponents {
                             material: board_handles.water.clone(),
                             transform: Transform {
                                 translation: Vec3::new(
-                                    x_to(x as i32, ratio),
-                                    y_to(y as i32, ratio),
+                                    layout.x_to(x as i32),
+                                    layout.y_to(y as i32),
                                     Z_BACKGROUND,
                                 ),
                                 scale: Vec3::splat(ratio),
@@ -0,0 +1,37 @@
+use super::*;
+
+use crate::bindings::Action;
+use crate::gamepad::GamepadActions;
+use keyboard_systems::PlayerInput;
+
+use simulation::Bot;
+
+/// Let bots play the demo game, and go back to the menu on any input
+pub fn demo_system(
+    (mut game_screen, game): (ResMut<crate::GameScreen>, Res<Game>),
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    gamepad: Res<GamepadActions>,
+    mut player_input: ResMut<Events<PlayerInput>>,
+) {
+    if game_screen.current_screen != CURRENT_SCREEN || !game_screen.demo {
+        return;
+    }
+    if keyboard_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+        || Action::ALL
+            .iter()
+            .any(|action| gamepad.just_pressed(*action))
+    {
+        game_screen.demo = false;
+        game_screen.current_screen = crate::Screen::Menu;
+        return;
+    }
+    if game.state == GameState::Play && game.board.is_some() {
+        for player in 0..game.sim.players().len() {
+            if let Some(action) = Bot::new(player).action(&game.sim) {
+                player_input.send(PlayerInput { player, action });
+            }
+        }
+    }
+}
@@ -1,101 +1,69 @@
 use bevy::prelude::*;
 use rand::Rng;
 
-use super::{Game, GameEvents};
+use super::{EmoteHolder, Game, GameEvents, GameRng, Layout};
 
 pub fn emote_setter(
     mut commands: Commands,
     (mut event_reader, events): (Local<EventReader<GameEvents>>, Res<Events<GameEvents>>),
     asset_handles: Res<crate::AssetHandles>,
     game: Res<Game>,
-    (wnds, time): (Res<Windows>, Res<Time>),
-    _emote_holder: &super::EmoteHolder,
-    entity: Entity,
+    mut rng: ResMut<GameRng>,
+    layout: Res<Layout>,
+    holders: Query<(Entity, &EmoteHolder)>,
 ) {
-    let ratio = wnds.get_primary().unwrap().width() as f32
-        / super::BOARD_X as f32
-        / super::TILE_SIZE as f32;
+    let ratio = layout.ratio;
     let emotes = asset_handles.get_emote_handles_unsafe();
+    let events = event_reader.iter(&events).collect::<Vec<_>>();
 
-    if game.laser.x == game.player.x || game.laser.x == game.player.x + 1 {
-        commands
-            .spawn(SpriteComponents {
-                transform: Transform::from_scale(Vec3::splat(ratio * 0.7)),
-                material: emotes.exclamation,
-                ..Default::default()
-            })
-            .with(crate::menu::Emote(Timer::from_seconds(1., false)));
-        let emote = commands.current_entity().unwrap();
-        commands.push_children(entity, &[emote]);
-        return;
-    }
-    if game.time_last_move > 0. && time.seconds_since_startup - game.time_last_move > 3. {
-        commands
-            .spawn(SpriteComponents {
-                transform: Transform::from_scale(Vec3::splat(ratio * 0.7)),
-                material: emotes.sleep,
-                ..Default::default()
-            })
-            .with(crate::menu::Emote(Timer::from_seconds(0.3, false)));
-        let emote = commands.current_entity().unwrap();
-        commands.push_children(entity, &[emote]);
-        return;
-    }
-    for event in event_reader.iter(&events) {
-        match event {
-            GameEvents::NewRound => {
-                if game.round % 2 == 0 {
-                    commands
-                        .spawn(SpriteComponents {
-                            transform: Transform::from_scale(Vec3::splat(ratio * 0.7)),
-                            material: emotes.face_angry,
-                            ..Default::default()
-                        })
-                        .with(crate::menu::Emote(Timer::from_seconds(1., false)));
-                    let emote = commands.current_entity().unwrap();
-                    commands.push_children(entity, &[emote]);
-                    return;
+    for (entity, holder) in holders.iter() {
+        let player = &game.sim.players()[holder.0];
+        let laser = game.sim.laser();
+
+        let emote = if player.is_alive() && (laser.x == player.x || laser.x == player.x + 1) {
+            Some((emotes.exclamation.clone(), 1.))
+        } else if player.is_alive()
+            && game
+                .sim
+                .time_since_last_move(holder.0)
+                .map(|since| since > 3000)
+                .unwrap_or(false)
+        {
+            Some((emotes.sleep.clone(), 0.3))
+        } else {
+            events.iter().find_map(|event| match event {
+                GameEvents::NewRound if game.sim.round() % 2 == 0 => {
+                    Some((emotes.face_angry.clone(), 1.))
                 }
-            }
-            GameEvents::Lost(_) => {
-                commands
-                    .spawn(SpriteComponents {
-                        transform: Transform::from_scale(Vec3::splat(ratio * 0.7)),
-                        material: emotes.heart_broken,
-                        ..Default::default()
-                    })
-                    .with(crate::menu::Emote(Timer::from_seconds(5., false)));
-                let emote = commands.current_entity().unwrap();
-                commands.push_children(entity, &[emote]);
-                return;
-            }
-            GameEvents::NewHighround | GameEvents::NewHighscore => {
-                commands
-                    .spawn(SpriteComponents {
-                        transform: Transform::from_scale(Vec3::splat(ratio * 0.7)),
-                        material: emotes.star,
-                        ..Default::default()
-                    })
-                    .with(crate::menu::Emote(Timer::from_seconds(1., false)));
-                let emote = commands.current_entity().unwrap();
-                commands.push_children(entity, &[emote]);
-                return;
-            }
-            GameEvents::PlayerBonus => {
-                if rand::thread_rng().gen_bool(0.25) {
-                    commands
-                        .spawn(SpriteComponents {
-                            transform: Transform::from_scale(Vec3::splat(ratio * 0.7)),
-                            material: emotes.face_happy,
-                            ..Default::default()
-                        })
-                        .with(crate::menu::Emote(Timer::from_seconds(0.5, false)));
-                    let emote = commands.current_entity().unwrap();
-                    commands.push_children(entity, &[emote]);
-                    return;
+                GameEvents::Lost(down, _) if *down == holder.0 => {
+                    Some((emotes.heart_broken.clone(), 5.))
                 }
-            }
-            GameEvents::Pause => (),
+                GameEvents::GameOver if player.is_alive() => Some((emotes.star.clone(), 5.)),
+                GameEvents::PlayerRevived(revived) if *revived == holder.0 => {
+                    Some((emotes.face_happy.clone(), 1.))
+                }
+                GameEvents::NewHighround | GameEvents::NewHighscore => {
+                    Some((emotes.star.clone(), 1.))
+                }
+                GameEvents::PlayerBonus(bonus_player)
+                    if *bonus_player == holder.0 && rng.rng().gen_bool(0.25) =>
+                {
+                    Some((emotes.face_happy.clone(), 0.5))
+                }
+                _ => None,
+            })
         };
+
+        if let Some((material, duration)) = emote {
+            commands
+                .spawn(SpriteComponents {
+                    transform: Transform::from_scale(Vec3::splat(ratio * 0.7)),
+                    material,
+                    ..Default::default()
+                })
+                .with(crate::menu::Emote(Timer::from_seconds(duration, false)));
+            let emote = commands.current_entity().unwrap();
+            commands.push_children(entity, &[emote]);
+        }
     }
 }
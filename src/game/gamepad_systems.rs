@@ -0,0 +1,80 @@
+use super::*;
+
+use crate::bindings::Action;
+use crate::gamepad::GamepadActions;
+use keyboard_systems::{select_pause_menu_item, toggle_pause, PlayerInput};
+
+use simulation::{MoveDirection, PlayerAction};
+
+pub fn gamepad_system(
+    mut commands: Commands,
+    (mut game_screen, mut game, mut screen): (
+        ResMut<crate::GameScreen>,
+        ResMut<Game>,
+        ResMut<Screen>,
+    ),
+    gamepad: Res<GamepadActions>,
+    mut game_events: ResMut<Events<GameEvents>>,
+    mut player_input: ResMut<Events<PlayerInput>>,
+) {
+    if game_screen.current_screen != CURRENT_SCREEN || game_screen.demo {
+        return;
+    }
+    if gamepad.just_pressed(Action::Pause) {
+        toggle_pause(&mut commands, &mut game, &mut screen, &mut game_events);
+    } else if game.state == GameState::Play {
+        if game.sim.is_replay() {
+            return;
+        }
+        // with several players, each gamepad controls its own player
+        let nb_players = game.sim.players().len();
+        for pad in 0..gamepad.nb_connected() {
+            let player = if nb_players == 1 { 0 } else { pad };
+            if player >= nb_players {
+                break;
+            }
+            for (action, direction) in &[
+                (Action::Left, MoveDirection::Left),
+                (Action::Right, MoveDirection::Right),
+                (Action::Up, MoveDirection::Up),
+                (Action::Down, MoveDirection::Down),
+            ] {
+                if gamepad.pressed_on(pad, *action) {
+                    player_input.send(PlayerInput {
+                        player,
+                        action: PlayerAction::Move(*direction),
+                    });
+                }
+            }
+            if gamepad.just_pressed_on(pad, Action::Bomb) && game.sim.bombs_available(player) > 0 {
+                player_input.send(PlayerInput {
+                    player,
+                    action: PlayerAction::PoseBomb,
+                });
+            }
+            if gamepad.just_pressed_on(pad, Action::Detonate) && game.sim.has_waiting_bombs(player)
+            {
+                player_input.send(PlayerInput {
+                    player,
+                    action: PlayerAction::Detonate,
+                });
+            }
+        }
+    } else if let GameState::Pause(pause_entity) = game.state {
+        if gamepad.just_pressed(Action::Left) {
+            screen.pause_menu_selector = 0;
+        } else if gamepad.just_pressed(Action::Right) {
+            screen.pause_menu_selector = 1;
+        } else if gamepad.just_pressed(Action::Confirm) {
+            select_pause_menu_item(
+                &mut commands,
+                &mut game,
+                &mut game_screen,
+                &screen,
+                pause_entity,
+            );
+        } else if gamepad.just_pressed(Action::Back) {
+            toggle_pause(&mut commands, &mut game, &mut screen, &mut game_events);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use bevy::prelude::*;
+use simulation::{JournalEntry, JournalEvent, Simulation};
+use tracing::{info, warn};
+
+use super::{replay::timestamp, Game};
+
+const JOURNAL_FOLDER: &str = "journals";
+
+/// Journal of the current game, written as one JSON object per line.
+#[derive(Default)]
+pub struct GameJournal(Option<(String, BufWriter<File>)>);
+
+impl GameJournal {
+    /// Start the journal of a new game. Replays are not journaled again.
+    pub fn start(sim: &mut Simulation) -> Self {
+        if sim.is_replay() {
+            return GameJournal(None);
+        }
+        let path = format!("{}/{}-{}.jsonl", JOURNAL_FOLDER, timestamp(), sim.seed());
+        if let Err(err) = std::fs::create_dir_all(JOURNAL_FOLDER) {
+            warn!("error creating folder {}: {}", JOURNAL_FOLDER, err);
+            return GameJournal(None);
+        }
+        match File::create(&path) {
+            Ok(file) => {
+                sim.start_journal();
+                GameJournal(Some((path, BufWriter::new(file))))
+            }
+            Err(err) => {
+                warn!("error creating journal {}: {}", path, err);
+                GameJournal(None)
+            }
+        }
+    }
+
+    fn write(&mut self, entry: &JournalEntry) {
+        if let Some((path, writer)) = self.0.as_mut() {
+            let written = serde_json::to_writer(&mut *writer, entry)
+                .map_err(|err| err.to_string())
+                .and_then(|_| writeln!(writer).map_err(|err| err.to_string()));
+            if let Err(err) = written {
+                warn!("error writing journal {}: {}", path, err);
+                self.0 = None;
+            }
+        }
+    }
+
+    /// Stop writing the journal, flushing what is left.
+    pub fn end(&mut self) {
+        if let Some((path, mut writer)) = self.0.take() {
+            match writer.flush() {
+                Ok(()) => info!("journal saved to {}", path),
+                Err(err) => warn!("error writing journal {}: {}", path, err),
+            }
+        }
+    }
+}
+
+pub fn record_journal(mut game: ResMut<Game>, mut journal: ResMut<GameJournal>) {
+    for entry in game.sim.take_journal() {
+        journal.write(&entry);
+        if let JournalEvent::GameOver { .. } = entry.event {
+            journal.end();
+        }
+    }
+}
@@ -1,36 +1,44 @@
 use super::*;
 
-#[derive(Debug, Clone, Copy)]
-pub enum MoveDirection {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use crate::bindings::{Action, Bindings, PLAYER_ACTIONS};
+
+pub use simulation::{MoveDirection, PlayerAction};
 
-#[derive(Debug)]
-pub enum PlayerAction {
-    PoseBomb,
-    Move(MoveDirection),
+/// An action done by one of the players
+pub struct PlayerInput {
+    pub player: usize,
+    pub action: PlayerAction,
 }
 
 pub fn keyboard_input_system(
     keyboard_input: Res<Input<KeyCode>>,
+    bindings: Res<Bindings>,
     (game_screen, game): (Res<crate::GameScreen>, Res<Game>),
-    mut player_action: ResMut<Events<PlayerAction>>,
+    mut player_input: ResMut<Events<PlayerInput>>,
 ) {
-    if game_screen.current_screen == CURRENT_SCREEN && game.state == GameState::Play {
-        if keyboard_input.pressed(KeyCode::Left) {
-            player_action.send(PlayerAction::Move(MoveDirection::Left));
-        }
-        if keyboard_input.pressed(KeyCode::Right) {
-            player_action.send(PlayerAction::Move(MoveDirection::Right));
-        }
-        if keyboard_input.pressed(KeyCode::Up) {
-            player_action.send(PlayerAction::Move(MoveDirection::Up));
-        }
-        if keyboard_input.pressed(KeyCode::Down) {
-            player_action.send(PlayerAction::Move(MoveDirection::Down));
+    if game_screen.current_screen == CURRENT_SCREEN
+        && game.state == GameState::Play
+        && !game.sim.is_replay()
+        && !game_screen.demo
+    {
+        for (player, actions) in PLAYER_ACTIONS
+            .iter()
+            .enumerate()
+            .take(game.sim.players().len())
+        {
+            for (action, direction) in &[
+                (actions.left, MoveDirection::Left),
+                (actions.right, MoveDirection::Right),
+                (actions.up, MoveDirection::Up),
+                (actions.down, MoveDirection::Down),
+            ] {
+                if bindings.pressed(&keyboard_input, *action) {
+                    player_input.send(PlayerInput {
+                        player,
+                        action: PlayerAction::Move(*direction),
+                    });
+                }
+            }
         }
     }
 }
@@ -47,54 +55,55 @@ pub fn keyboard_event_system(
         Res<Events<KeyboardInput>>,
     ),
     mut game_events: ResMut<Events<GameEvents>>,
-    mut player_action: ResMut<Events<PlayerAction>>,
-    used_bomb: Query<&BombComponent>,
+    mut player_input: ResMut<Events<PlayerInput>>,
+    bindings: Res<Bindings>,
 ) {
-    if game_screen.current_screen == CURRENT_SCREEN {
+    if game_screen.current_screen == CURRENT_SCREEN && !game_screen.demo {
         for event in event_reader.iter(&keyboard_input_events) {
-            if (event.key_code == Some(KeyCode::P) || event.key_code == Some(KeyCode::Escape))
+            let is = |action| {
+                event
+                    .key_code
+                    .map(|key| bindings.is(key, action))
+                    .unwrap_or(false)
+            };
+            if is(Action::Pause) && event.state == ElementState::Pressed {
+                toggle_pause(&mut commands, &mut game, &mut screen, &mut game_events);
+            } else if game.state == GameState::Play
                 && event.state == ElementState::Pressed
+                && !game.sim.is_replay()
             {
-                if let GameState::Pause(entity) = game.state {
-                    commands.despawn_recursive(entity);
-                    game.state = GameState::Play
-                } else {
-                    screen.pause_menu_selector = 1;
-                    game_events.send(GameEvents::Pause);
-                };
-            } else if game.state == GameState::Play && event.state == ElementState::Pressed {
-                match event.key_code {
-                    Some(KeyCode::Space) => {
-                        if game.player.nb_bombs > used_bomb.iter().count() {
-                            player_action.send(PlayerAction::PoseBomb);
-                        }
+                for (player, actions) in PLAYER_ACTIONS
+                    .iter()
+                    .enumerate()
+                    .take(game.sim.players().len())
+                {
+                    if is(actions.bomb) && game.sim.bombs_available(player) > 0 {
+                        player_input.send(PlayerInput {
+                            player,
+                            action: PlayerAction::PoseBomb,
+                        });
+                    }
+                    if is(actions.detonate) && game.sim.has_waiting_bombs(player) {
+                        player_input.send(PlayerInput {
+                            player,
+                            action: PlayerAction::Detonate,
+                        });
                     }
-                    _ => (),
                 }
             } else if let GameState::Pause(pause_entity) = game.state {
                 if event.state == ElementState::Pressed {
-                    match event.key_code {
-                        Some(KeyCode::Left) => {
-                            screen.pause_menu_selector = 0;
-                        }
-                        Some(KeyCode::Right) => {
-                            screen.pause_menu_selector = 1;
-                        }
-                        Some(KeyCode::Return) | Some(KeyCode::Space) => {
-                            match screen.pause_menu_selector {
-                                1 => {
-                                    commands.despawn_recursive(pause_entity);
-                                    game.state = GameState::Play
-                                }
-                                0 => {
-                                    commands.despawn_recursive(pause_entity);
-                                    game.state = GameState::Death;
-                                    game_screen.current_screen = crate::Screen::Menu;
-                                }
-                                _ => (),
-                            }
-                        }
-                        _ => (),
+                    if is(Action::Left) {
+                        screen.pause_menu_selector = 0;
+                    } else if is(Action::Right) {
+                        screen.pause_menu_selector = 1;
+                    } else if is(Action::Confirm) {
+                        select_pause_menu_item(
+                            &mut commands,
+                            &mut game,
+                            &mut game_screen,
+                            &screen,
+                            pause_entity,
+                        );
                     }
                 }
             }
@@ -102,260 +111,38 @@ pub fn keyboard_event_system(
     }
 }
 
-pub fn player_move_timer(time: Res<Time>, mut player: Mut<PlayerComponent>) {
-    if let Some(timer) = player.0.as_mut() {
-        timer.tick(time.delta_seconds);
-    }
+pub(super) fn toggle_pause(
+    commands: &mut Commands,
+    game: &mut Game,
+    screen: &mut Screen,
+    game_events: &mut Events<GameEvents>,
+) {
+    if let GameState::Pause(entity) = game.state {
+        commands.despawn_recursive(entity);
+        game.state = GameState::Play
+    } else {
+        screen.pause_menu_selector = 1;
+        game_events.send(GameEvents::Pause);
+    };
 }
 
-pub fn player_command(
-    mut commands: Commands,
-    mut game: ResMut<Game>,
-    (mut event_reader, events): (Local<EventReader<PlayerAction>>, Res<Events<PlayerAction>>),
-    asset_handles: Res<crate::AssetHandles>,
-    (wnds, time): (Res<Windows>, Res<Time>),
-    mut interesting_events: ResMut<Events<InterestingEvent>>,
-    mut player_query: Query<Without<PlayerMoving, (Entity, &mut PlayerComponent, &Transform)>>,
-    occupied_tiles: Query<(Entity, &super::laser::ObstacleComponent)>,
-    chained_eased_query: Query<&bevy_easings::EasingChainComponent<Transform>>,
+pub(super) fn select_pause_menu_item(
+    commands: &mut Commands,
+    game: &mut Game,
+    game_screen: &mut crate::GameScreen,
+    screen: &Screen,
+    pause_entity: Entity,
 ) {
-    let ratio = wnds.get_primary().unwrap().width() as f32 / BOARD_X as f32 / TILE_SIZE as f32;
-    if game.board.is_some() && game.state == GameState::Play {
-        let mut moved = false;
-        for event in event_reader.iter(&events) {
-            match (event, moved) {
-                (PlayerAction::PoseBomb, _) => {
-                    let bomb_handle = asset_handles.get_board_handles_unsafe().bomb;
-                    commands
-                        .spawn(SpriteComponents {
-                            material: bomb_handle.clone(),
-                            transform: Transform {
-                                translation: Vec3::new(0., 0., Z_BOMB),
-                                scale: Vec3::splat(ratio * 0.6),
-                                ..Default::default()
-                            },
-                            ..Default::default()
-                        })
-                        .with(BombSprite);
-                    let bomb = commands.current_entity().unwrap();
-                    let entity = game.board.as_ref().unwrap()[game.player.y][game.player.x].entity;
-                    commands.push_children(entity, &[bomb]);
-                    commands.insert(
-                        entity,
-                        (
-                            Occupied,
-                            BombComponent {
-                                damage: game.player.bomb_damage,
-                                range: game.player.bomb_range,
-                                state: BombState::Fuse,
-                                x: game.player.x,
-                                y: game.player.y,
-                                timer: Timer::from_seconds(
-                                    game.player.bomb_speed as f32 / 1000. / 2.,
-                                    false,
-                                ),
-                            },
-                        ),
-                    );
-                    interesting_events.send(InterestingEvent::BombPlaced);
-                }
-                (PlayerAction::Move(direction), false) => {
-                    let change = match direction {
-                        MoveDirection::Left => (-1, 0),
-                        MoveDirection::Right => (1, 0),
-                        MoveDirection::Up => (0, 1),
-                        MoveDirection::Down => (0, -1),
-                    };
-                    let x = game.player.x as i32 + change.0;
-                    let y = game.player.y as i32 + change.1;
-                    let mut move_to_do = MoveToDo::Move(*direction, x, y);
-                    if y < 0 || y >= BOARD_Y as i32 {
-                        move_to_do = MoveToDo::Bump(*direction);
-                    } else if x < 0 {
-                        if occupied_tiles
-                            .get_component::<super::laser::ObstacleComponent>(
-                                game.board.as_ref().unwrap()[y as usize][BOARD_X - 1].entity,
-                            )
-                            .is_ok()
-                        {
-                            move_to_do = MoveToDo::Bump(*direction);
-                        } else {
-                            move_to_do = MoveToDo::Teleport(BOARD_X as u32 - 1, y as u32);
-                        }
-                    } else if x >= BOARD_X as i32 {
-                        move_to_do = MoveToDo::Teleport(0, y as u32);
-                    } else if occupied_tiles
-                        .get_component::<super::laser::ObstacleComponent>(
-                            game.board.as_ref().unwrap()[y as usize][x as usize].entity,
-                        )
-                        .is_ok()
-                    {
-                        move_to_do = MoveToDo::Bump(*direction);
-                    }
-
-                    for (entity, mut player, transform) in player_query.iter_mut() {
-                        if chained_eased_query
-                            .get_component::<bevy_easings::EasingChainComponent<Transform>>(entity)
-                            .is_ok()
-                        {
-                            commands.remove_one::<bevy_easings::EasingChainComponent<Transform>>(
-                                entity,
-                            );
-                        }
-
-                        let mut remaining_move: u64 = 0;
-                        if let Some(timer) = player.0.as_ref() {
-                            remaining_move =
-                                u64::max(0, ((timer.duration - timer.elapsed) * 1000.) as u64);
-                        }
-                        let move_delay = game.player.speed + remaining_move;
-                        let buffer_delay = move_delay * 7 / 10;
-
-                        let base_transform = Transform {
-                            translation: Vec3::new(
-                                x_to(game.player.x as i32, ratio),
-                                y_to(game.player.y as i32, ratio),
-                                Z_PLAYER,
-                            ),
-                            scale: match game.player.direction {
-                                FacingDirection::Right => Vec3::new(1., 1., 1.),
-                                FacingDirection::Left => Vec3::new(-1., 1., 1.),
-                            },
-                            ..Default::default()
-                        };
-
-                        let (to_x, to_y) = match move_to_do {
-                            MoveToDo::Move(direction, x, y) => {
-                                match direction {
-                                    MoveDirection::Left => {
-                                        game.player.direction = FacingDirection::Left
-                                    }
-                                    MoveDirection::Right => {
-                                        game.player.direction = FacingDirection::Right
-                                    }
-                                    _ => (),
-                                }
-                                commands.insert_one(
-                                    entity,
-                                    transform.ease_to(
-                                        Transform {
-                                            translation: Vec3::new(
-                                                x_to(x as i32, ratio),
-                                                y_to(y as i32, ratio),
-                                                Z_PLAYER,
-                                            ),
-                                            scale: match game.player.direction {
-                                                FacingDirection::Right => Vec3::new(1., 1., 1.),
-                                                FacingDirection::Left => Vec3::new(-1., 1., 1.),
-                                            },
-                                            ..Default::default()
-                                        },
-                                        bevy_easings::EaseMethod::Linear,
-                                        bevy_easings::EasingType::Once {
-                                            duration: std::time::Duration::from_millis(move_delay),
-                                        },
-                                    ),
-                                );
-                                (x as usize, y as usize)
-                            }
-                            MoveToDo::Teleport(x, y) => {
-                                commands.insert_one(
-                                    entity,
-                                    transform.ease_to(
-                                        Transform {
-                                            translation: Vec3::new(
-                                                x_to(x as i32, ratio),
-                                                y_to(y as i32, ratio),
-                                                Z_PLAYER,
-                                            ),
-                                            ..base_transform
-                                        },
-                                        bevy_easings::EaseMethod::Discrete,
-                                        bevy_easings::EasingType::Once {
-                                            duration: std::time::Duration::from_millis(move_delay),
-                                        },
-                                    ),
-                                );
-                                (x as usize, y as usize)
-                            }
-                            MoveToDo::Bump(_) => {
-                                commands.insert_one(
-                                    entity,
-                                    transform
-                                        .ease_to(
-                                            Transform {
-                                                translation: Vec3::new(
-                                                    x_to(game.player.x as i32, ratio)
-                                                        + change.0 as f32
-                                                            * 0.65
-                                                            * ratio
-                                                            * TILE_SIZE as f32
-                                                            / 2.,
-                                                    y_to(game.player.y as i32, ratio)
-                                                        + change.1 as f32
-                                                            * 0.65
-                                                            * ratio
-                                                            * TILE_SIZE as f32
-                                                            / 2.,
-                                                    Z_PLAYER,
-                                                ),
-                                                ..base_transform
-                                            },
-                                            bevy_easings::EaseFunction::QuadraticIn,
-                                            bevy_easings::EasingType::Once {
-                                                duration: std::time::Duration::from_millis(
-                                                    move_delay / 4,
-                                                ),
-                                            },
-                                        )
-                                        .ease_to(
-                                            Transform {
-                                                translation: Vec3::new(
-                                                    x_to(game.player.x as i32, ratio),
-                                                    y_to(game.player.y as i32, ratio),
-                                                    Z_PLAYER,
-                                                ),
-                                                ..base_transform
-                                            },
-                                            bevy_easings::EaseFunction::QuadraticOut,
-                                            bevy_easings::EasingType::Once {
-                                                duration: std::time::Duration::from_millis(
-                                                    move_delay / 4,
-                                                ),
-                                            },
-                                        ),
-                                );
-                                (game.player.x, game.player.y)
-                            }
-                        };
-                        game.time_last_move = time.seconds_since_startup;
-                        commands.insert_one(
-                            entity,
-                            PlayerMoving {
-                                timer_before_allow_new_move: Timer::new(
-                                    std::time::Duration::from_millis(buffer_delay),
-                                    false,
-                                ),
-                                timer_before_update_position: Timer::new(
-                                    std::time::Duration::from_millis(move_delay / 2),
-                                    false,
-                                ),
-                                to: (to_x, to_y),
-                            },
-                        );
-                        player.0 = Some(Timer::from_seconds(move_delay as f32 / 1000., false));
-                        moved = true;
-                    }
-                }
-                _ => (),
-            }
+    match screen.pause_menu_selector {
+        1 => {
+            commands.despawn_recursive(pause_entity);
+            game.state = GameState::Play
         }
+        0 => {
+            commands.despawn_recursive(pause_entity);
+            game.state = GameState::Death;
+            game_screen.current_screen = crate::Screen::Menu;
+        }
+        _ => (),
     }
 }
-
-#[derive(Debug)]
-enum MoveToDo {
-    Move(MoveDirection, i32, i32),
-    Teleport(u32, u32),
-    Bump(MoveDirection),
-}
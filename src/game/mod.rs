@@ -1,16 +1,28 @@
 use bevy::{
     input::{keyboard::KeyboardInput, ElementState},
     prelude::*,
+    window::WindowResized,
 };
+use rand::{rngs::StdRng, SeedableRng};
 use tracing::info;
 
 mod board_setup;
 mod bomb;
+mod demo_systems;
 mod emote;
+mod gamepad_systems;
+mod journal;
 mod keyboard_systems;
 mod laser;
+mod player;
+mod replay;
 mod ui;
 
+pub(crate) use board_setup::ground_material;
+pub use replay::{save_replay, ReplayMode};
+use simulation::{BombState, FacingDirection, Simulation};
+pub use simulation::{CauseOfDeath, Mode, PlayerPowerUp};
+
 use bevy_easings::Ease;
 
 const CURRENT_SCREEN: crate::Screen = crate::Screen::Game;
@@ -37,40 +49,48 @@ impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut AppBuilder) {
         app.add_resource(Screen::default())
             .init_resource::<Game>()
+            .init_resource::<Layout>()
+            .init_resource::<journal::GameJournal>()
+            .init_resource::<TestPlayArena>()
             .add_event::<GameEvents>()
-            .add_event::<keyboard_systems::PlayerAction>()
+            .add_event::<keyboard_systems::PlayerInput>()
             .add_event::<InterestingEvent>()
+            .add_event::<simulation::Event>()
             .add_system(keyboard_systems::keyboard_event_system.system())
             .add_system(keyboard_systems::keyboard_input_system.system())
-            .add_system(keyboard_systems::player_command.system())
-            .add_system(keyboard_systems::player_move_timer.system())
+            .add_system(gamepad_systems::gamepad_system.system())
+            .add_system(demo_systems::demo_system.system())
+            .add_system(step_simulation.system())
+            .add_system(journal::record_journal.system())
+            .add_system(player::move_player.system())
             .add_system(board_setup::setup.system())
             .add_system(ui::setup.system())
-            .add_system(laser::setup.system())
+            .add_system(relayout.system())
             .add_system(setup.system())
             .add_system(walk_animate_sprite_system.system())
             .add_system(board_setup::remove_indications.system())
-            .add_system(clear_moving_marker.system())
             .add_system(laser::jitter_laser.system())
             .add_system(laser::move_laser.system())
-            .add_system(laser::spawn_obstacles.system())
-            .add_system(laser::update_obstacle_sprite.system())
+            .add_system(laser::display_obstacles.system())
             .add_system(ui::ui_event_update.system())
             .add_system(ui::score.system())
             .add_system(ui::display_bombs_available.system())
+            .add_system(ui::display_lasting_powerups.system())
             .add_system(ui::death_animation.system())
             .add_system(ui::button_system.system())
             .add_system(ui::display_menu_item_selector.system())
-            .add_system(bomb::fire.system())
-            .add_system(bomb::flash_bombs.system())
-            .add_system(bomb::destroyed_obstacles.system())
-            .add_system(bomb::player_powerups.system())
+            .add_system(bomb::display_bombs.system())
+            .add_system(bomb::display_fires.system())
+            .add_system(bomb::display_powerups.system())
             .add_system(emote::emote_setter.system())
             .add_system_to_stage(crate::custom_stage::TEAR_DOWN, tear_down.system());
     }
 }
 
-pub struct EmoteHolder;
+pub struct EmoteHolder(pub usize);
+
+/// Tint of each player, to tell them apart
+pub(crate) const PLAYER_COLORS: [Color; 2] = [Color::WHITE, Color::rgb_linear(0.5, 0.7, 1.)];
 
 fn setup(
     mut commands: Commands,
@@ -79,83 +99,86 @@ fn setup(
     asset_server: Res<AssetServer>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    wnds: Res<Windows>,
+    layout: Res<Layout>,
 ) {
     if game_screen.current_screen == CURRENT_SCREEN && !screen.loaded {
         info!("Loading screen");
 
-        let ratio = wnds.get_primary().unwrap().width() as f32 / BOARD_X as f32 / TILE_SIZE as f32;
+        let ratio = layout.ratio;
 
         let board_handles = asset_handles.get_board_handles(&asset_server, &mut materials);
         let emotes = asset_handles.get_emote_handles(&asset_server, &mut materials);
 
         let character_handle =
             asset_handles.get_character_handle(&asset_server, &mut texture_atlases);
-        commands
-            .spawn((
-                Transform::from_translation(Vec3::new(
-                    x_to(game.player.x as i32, ratio),
-                    y_to(game.player.y as i32, ratio),
-                    Z_PLAYER,
-                )),
-                GlobalTransform::identity(),
-            ))
-            .with_children(|placed_player| {
-                placed_player
-                    .spawn(SpriteSheetComponents {
-                        texture_atlas: character_handle,
-                        transform: Transform::from_scale(Vec3::splat(
-                            ratio * TILE_SIZE / PLAYER_SIZE,
-                        )),
-                        sprite: TextureAtlasSprite {
-                            index: 36,
+        for (index, player) in game.sim.players().iter().enumerate() {
+            commands
+                .spawn((
+                    Transform::from_translation(Vec3::new(
+                        layout.x_to(player.x as i32),
+                        layout.y_to(player.y as i32),
+                        Z_PLAYER,
+                    )),
+                    GlobalTransform::identity(),
+                ))
+                .with_children(|placed_player| {
+                    placed_player
+                        .spawn(SpriteSheetComponents {
+                            texture_atlas: character_handle.clone(),
+                            transform: Transform::from_scale(Vec3::splat(
+                                ratio * TILE_SIZE / PLAYER_SIZE,
+                            )),
+                            sprite: TextureAtlasSprite {
+                                index: 36,
+                                color: PLAYER_COLORS[index % PLAYER_COLORS.len()],
+                            },
                             ..Default::default()
-                        },
-                        ..Default::default()
-                    })
-                    .with_bundle((Animation::Walk, Timer::from_seconds(0.1, true)));
-                placed_player
-                    .spawn((
-                        Transform::from_translation(Vec3::new(
-                            0.,
-                            ratio * (TILE_SIZE / 2.) * 1.1,
-                            10.,
-                        )),
-                        GlobalTransform::default(),
-                        EmoteHolder,
-                    ))
-                    .with_children(|emote| {
-                        emote
-                            .spawn(SpriteComponents {
-                                transform: Transform::from_scale(Vec3::splat(ratio * 0.7)),
-                                material: emotes.exclamations,
-                                ..Default::default()
-                            })
-                            .with(crate::menu::Emote(Timer::from_seconds(1., false)));
-                    });
-            })
-            .with(PlayerComponent(None))
-            .with(ScreenTag);
+                        })
+                        .with_bundle((
+                            Animation::Walk,
+                            Timer::from_seconds(0.1, true),
+                            PlayerSprite(index),
+                        ));
+                    placed_player
+                        .spawn((
+                            Transform::from_translation(Vec3::new(
+                                0.,
+                                ratio * (TILE_SIZE / 2.) * 1.1,
+                                10.,
+                            )),
+                            GlobalTransform::default(),
+                            EmoteHolder(index),
+                        ))
+                        .with_children(|emote| {
+                            emote
+                                .spawn(SpriteComponents {
+                                    transform: Transform::from_scale(Vec3::splat(ratio * 0.7)),
+                                    material: emotes.exclamations.clone(),
+                                    ..Default::default()
+                                })
+                                .with(crate::menu::Emote(Timer::from_seconds(1., false)));
+                        });
+                })
+                .with(PlayerComponent(index))
+                .with(ScreenTag);
+        }
 
         commands.spawn((
             Transform::from_translation(Vec3::new(
-                x_to(game.laser.x as i32 - 1, ratio),
+                layout.x_to(game.sim.laser().x as i32 - 1),
                 1.,
                 Z_LASER,
             )),
             GlobalTransform::identity(),
-            LaserComponent(Timer::new(
-                std::time::Duration::from_millis(game.laser.speed),
-                true,
-            )),
+            LaserComponent,
         ));
-        for y in 0..BOARD_Y + 5 {
+        for y in 0..game.sim.board().height() + 5 {
             commands
                 .with_children(|laser_parent| {
                     laser_parent.spawn(SpriteComponents {
                         material: board_handles.laser.clone(),
                         transform: Transform {
-                            translation: Vec3::new(1., y_to(y as i32 - 2, ratio), 1.),
+                            translation: Vec3::new(1., layout.y_to(y as i32 - 2), 1.),
                             scale: Vec3::new(3. * ratio, ratio, 1.),
                             ..Default::default()
                         },
@@ -175,57 +198,33 @@ fn tear_down(
     game_screen: Res<crate::GameScreen>,
     mut screen: ResMut<Screen>,
     query: Query<With<ScreenTag, Entity>>,
+    mut camera_query: Query<With<crate::WorldCamera, &mut Transform>>,
+    mut journal: ResMut<journal::GameJournal>,
 ) {
     if game_screen.current_screen != CURRENT_SCREEN && screen.loaded {
         info!("tear down");
 
+        journal.end();
+
         for entity in &mut query.iter() {
             commands.despawn_recursive(entity);
         }
+        for mut transform in camera_query.iter_mut() {
+            transform.scale = Vec3::one();
+        }
 
         screen.loaded = false;
     }
 }
 
-pub struct LaserComponent(Timer);
+pub struct LaserComponent;
 
-pub struct PlayerComponent(Option<Timer>);
+pub struct PlayerComponent(pub usize);
 
-#[derive(PartialEq)]
-pub enum BombState {
-    Fuse,
-    Flash,
-}
+pub struct PlayerSprite(pub usize);
 
 pub struct FireSprite;
-pub struct FireComponent {
-    damage: usize,
-    x: usize,
-    y: usize,
-    timer: Timer,
-    from_player: bool,
-}
-
 pub struct BombSprite;
-pub struct BombComponent {
-    damage: usize,
-    range: usize,
-    state: BombState,
-    x: usize,
-    y: usize,
-    timer: Timer,
-}
-
-pub struct PlayerMoving {
-    timer_before_allow_new_move: Timer,
-    timer_before_update_position: Timer,
-    to: (usize, usize),
-}
-
-pub struct Occupied;
-
-const BOARD_X: usize = 21;
-const BOARD_Y: usize = 10;
 
 const Z_BACKGROUND: f32 = 0.0;
 const Z_OBSTACLE: f32 = 0.1;
@@ -236,83 +235,166 @@ const Z_FIRE: f32 = 0.4;
 const Z_INDICATION: f32 = 0.5;
 const Z_LASER: f32 = 0.5;
 
-const TILE_SIZE: f32 = 64.;
-const PLAYER_SIZE: f32 = 256.;
+pub(crate) const TILE_SIZE: f32 = 64.;
+pub(crate) const PLAYER_SIZE: f32 = 256.;
 
-fn x_to(x: i32, ratio: f32) -> f32 {
-    (x as f32 * TILE_SIZE - (TILE_SIZE * BOARD_X as f32) / 2. + TILE_SIZE as f32 / 2.) * ratio
+/// Where the board is displayed in the window
+#[derive(Clone, Copy, Debug)]
+pub struct Layout {
+    /// ratio the board was spawned with
+    pub ratio: f32,
+    width: usize,
+    height: usize,
 }
 
-fn y_to(y: i32, ratio: f32) -> f32 {
-    (y as f32 * TILE_SIZE - (TILE_SIZE * BOARD_Y as f32) / 2.) * ratio
+impl Default for Layout {
+    fn default() -> Self {
+        Layout {
+            ratio: 1.,
+            width: 1,
+            height: 1,
+        }
+    }
 }
 
-struct Cell {
-    entity: Entity,
-}
+impl Layout {
+    pub(crate) fn new(window: &Window, width: usize, height: usize) -> Self {
+        let mut layout = Layout {
+            ratio: 1.,
+            width,
+            height,
+        };
+        layout.ratio = layout.fit(window);
+        layout
+    }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, enum_utils::IterVariants)]
-enum PlayerPowerUp {
-    Score,
-    BombCount,
-    BombDamage,
-    BombRange,
-    BombSpeed,
-}
+    /// Ratio for the board to fit in `window`, keeping room for half a border around the board
+    /// vertically
+    fn fit(&self, window: &Window) -> f32 {
+        f32::min(
+            window.width() as f32 / self.width as f32,
+            window.height() as f32 / (self.height + 1) as f32,
+        ) / TILE_SIZE
+    }
 
-struct Player {
-    x: usize,
-    y: usize,
-    direction: FacingDirection,
-    nb_bombs: usize,
-    bomb_range: usize,
-    bomb_damage: usize,
-    bomb_speed: u64,
-    speed: u64,
-}
+    /// Scale of the camera so that the board spawned with `ratio` fits in `window`
+    fn camera_scale(&self, window: &Window) -> f32 {
+        self.ratio / self.fit(window)
+    }
 
-#[derive(Clone, Copy)]
-enum FacingDirection {
-    Left,
-    Right,
-}
+    /// Size of the bars on the sides and above and below the board when the window doesn't
+    /// have the same aspect ratio as the board, in pixels
+    fn margins(&self, window: &Window) -> (f32, f32) {
+        let fit = self.fit(window) * TILE_SIZE;
+        (
+            (window.width() as f32 - self.width as f32 * fit).max(0.) / 2.,
+            (window.height() as f32 - (self.height + 1) as f32 * fit).max(0.) / 2.,
+        )
+    }
 
-impl Default for Player {
-    fn default() -> Self {
-        Player {
-            x: BOARD_X / 4,
-            y: BOARD_Y / 2,
-            direction: FacingDirection::Right,
-            nb_bombs: crate::CONFIG.player_nb_bombs,
-            bomb_range: crate::CONFIG.player_bomb_range,
-            bomb_damage: crate::CONFIG.player_bomb_damage,
-            bomb_speed: crate::CONFIG.player_bomb_speed,
-            speed: crate::CONFIG.player_speed,
+    /// Position of a HUD element placed at `position` from the corners of the board
+    fn hud_position(&self, window: &Window, position: &Rect<Val>) -> Rect<Val> {
+        let (horizontal, vertical) = self.margins(window);
+        let shift = |value: Val, by: f32| match value {
+            Val::Px(px) => Val::Px(px + by),
+            value => value,
+        };
+        Rect {
+            left: shift(position.left, horizontal),
+            right: shift(position.right, horizontal),
+            top: shift(position.top, vertical),
+            bottom: shift(position.bottom, vertical),
         }
     }
+
+    pub(crate) fn x_to(&self, x: i32) -> f32 {
+        (x as f32 * TILE_SIZE - (TILE_SIZE * self.width as f32) / 2. + TILE_SIZE as f32 / 2.)
+            * self.ratio
+    }
+
+    pub(crate) fn y_to(&self, y: i32) -> f32 {
+        (y as f32 * TILE_SIZE - (TILE_SIZE * self.height as f32) / 2.) * self.ratio
+    }
+
+    /// Cell displayed at `position` in the world, if any
+    pub(crate) fn cell_at(&self, position: Vec2) -> Option<(usize, usize)> {
+        let tile = TILE_SIZE * self.ratio;
+        let x = (position.x() / tile + self.width as f32 / 2.).floor();
+        let y = (position.y() / tile + self.height as f32 / 2. + 0.5).floor();
+        if x < 0. || y < 0. || x >= self.width as f32 || y >= self.height as f32 {
+            None
+        } else {
+            Some((x as usize, y as usize))
+        }
+    }
+}
+
+/// A HUD element, with its position from the corners of the board
+pub struct Hud(Rect<Val>);
+
+/// Zoom the camera so that the board keeps its aspect ratio in the resized window, and move the
+/// HUD to the corners of the board.
+fn relayout(
+    game_screen: Res<crate::GameScreen>,
+    screen: Res<Screen>,
+    layout: Res<Layout>,
+    wnds: Res<Windows>,
+    (mut event_reader, events): (
+        Local<EventReader<WindowResized>>,
+        Res<Events<WindowResized>>,
+    ),
+    mut camera_query: Query<With<crate::WorldCamera, &mut Transform>>,
+    mut hud_query: Query<(&Hud, &mut Style)>,
+) {
+    if event_reader.iter(&events).last().is_none()
+        || game_screen.current_screen != CURRENT_SCREEN
+        || !screen.loaded
+    {
+        return;
+    }
+    let window = wnds.get_primary().unwrap();
+    info!("relayout for {}x{}", window.width(), window.height());
+    let scale = layout.camera_scale(window);
+    for mut transform in camera_query.iter_mut() {
+        transform.scale = Vec3::new(scale, scale, 1.);
+    }
+    for (hud, mut style) in hud_query.iter_mut() {
+        style.position = layout.hud_position(window, &hud.0);
+    }
 }
 
-#[derive(Debug)]
-struct Laser {
-    x: usize,
-    speed: u64,
-    spawn_obstacles_delay: u16,
-    nb_obstacles: usize,
-    obstacle_strength: usize,
+struct Cell {
+    entity: Entity,
+    obstacle: Option<Entity>,
+    bomb: Option<(Entity, BombState)>,
+    fire: Option<Entity>,
+    powerup: Option<Entity>,
 }
 
-impl Default for Laser {
-    fn default() -> Self {
-        Laser {
-            x: 1,
-            speed: crate::CONFIG.laser_speed,
-            spawn_obstacles_delay: crate::CONFIG.laser_spawn_obstacles_delay,
-            nb_obstacles: crate::CONFIG.laser_nb_obstacles,
-            obstacle_strength: crate::CONFIG.laser_obstacle_strength,
+impl Cell {
+    fn new(entity: Entity) -> Self {
+        Cell {
+            entity,
+            obstacle: None,
+            bomb: None,
+            fire: None,
+            powerup: None,
         }
     }
 }
 
+fn despawn_cell_sprite(
+    commands: &mut Commands,
+    children_query: &mut Query<&mut Children>,
+    cell: Entity,
+    sprite: Entity,
+) {
+    commands.despawn(sprite);
+    if let Ok(mut children) = children_query.get_component_mut::<Children>(cell) {
+        children.retain(|child| *child != sprite);
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum GameState {
     Play,
@@ -326,17 +408,161 @@ impl Default for GameState {
     }
 }
 
-#[derive(Default)]
 pub struct Game {
+    pub sim: Simulation,
     board: Option<Vec<Vec<Cell>>>,
-    player: Player,
-    laser: Laser,
-    pub round: u16,
-    pub score: u32,
-    time_last_move: f64,
     state: GameState,
 }
 
+impl Game {
+    pub fn new(
+        config: simulation::Config,
+        seed: u64,
+        mode: Mode,
+        arena: Option<simulation::Arena>,
+    ) -> Self {
+        let sim = match arena {
+            Some(arena) => Simulation::with_arena(config, seed, mode, arena),
+            None => Simulation::with_mode(config, seed, mode),
+        };
+        Game {
+            sim,
+            board: None,
+            state: GameState::default(),
+        }
+    }
+
+    pub fn from_replay(replay: &simulation::Replay) -> Self {
+        Game {
+            sim: Simulation::from_replay(replay),
+            board: None,
+            state: GameState::default(),
+        }
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new(
+            simulation::Config::default(),
+            rand::random(),
+            Mode::Solo,
+            None,
+        )
+    }
+}
+
+/// When set, games are played on this arena instead of the usual empty board.
+#[derive(Default)]
+pub struct ArenaMode(pub Option<simulation::Arena>);
+
+/// Arena for the next game only, set when test playing from the editor and taken by the board
+/// setup
+#[derive(Default)]
+pub struct TestPlayArena(pub Option<simulation::Arena>);
+
+/// Randomness for everything that is not gameplay (laser jitter, emotes...). It is seeded from the
+/// game seed, but drawn from every frame so it depends on the frame rate: only use it for cosmetics
+/// that don't need to be reproduced, gameplay randomness stays in the simulation
+pub struct GameRng {
+    fixed_seed: Option<u64>,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(fixed_seed: Option<u64>) -> Self {
+        GameRng {
+            fixed_seed,
+            rng: StdRng::seed_from_u64(fixed_seed.unwrap_or(0)),
+        }
+    }
+
+    pub fn new_game(&mut self) -> u64 {
+        let seed = self.fixed_seed.unwrap_or_else(rand::random);
+        self.reseed(seed);
+        seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
+
+fn step_simulation(
+    game_screen: Res<crate::GameScreen>,
+    time: Res<Time>,
+    mut game: ResMut<Game>,
+    (mut action_reader, actions): (
+        Local<EventReader<keyboard_systems::PlayerInput>>,
+        Res<Events<keyboard_systems::PlayerInput>>,
+    ),
+    mut simulation_events: ResMut<Events<simulation::Event>>,
+    mut game_events: ResMut<Events<GameEvents>>,
+    mut interesting_events: ResMut<Events<InterestingEvent>>,
+) {
+    let actions = action_reader
+        .iter(&actions)
+        .map(|input| (input.player, input.action))
+        .collect::<Vec<_>>();
+    if game_screen.current_screen == CURRENT_SCREEN
+        && game.state == GameState::Play
+        && game.board.is_some()
+    {
+        for event in game.sim.step(time.delta_seconds, &actions) {
+            match &event {
+                simulation::Event::NewRound => game_events.send(GameEvents::NewRound),
+                simulation::Event::Lost(_) => game_events.send(GameEvents::GameOver),
+                simulation::Event::PlayerDied { player, cause } => {
+                    game_events.send(GameEvents::Lost(*player, *cause))
+                }
+                simulation::Event::PlayerRevived { player } => {
+                    game_events.send(GameEvents::PlayerRevived(*player))
+                }
+                simulation::Event::PlayerBonus { player, .. } => {
+                    game_events.send(GameEvents::PlayerBonus(*player))
+                }
+                simulation::Event::BombPlaced { player, .. } => {
+                    interesting_events.send(InterestingEvent::BombPlaced(*player))
+                }
+                simulation::Event::BombExploded {
+                    player,
+                    chained: true,
+                    ..
+                } => interesting_events.send(InterestingEvent::BombChainDetonated(*player)),
+                simulation::Event::BombExploded { player, .. } => {
+                    interesting_events.send(InterestingEvent::BombExploded(*player))
+                }
+                simulation::Event::BombKicked { player, .. } => {
+                    interesting_events.send(InterestingEvent::BombKicked(*player))
+                }
+                simulation::Event::ShieldUsed { player } => {
+                    interesting_events.send(InterestingEvent::ShieldUsed(*player))
+                }
+                simulation::Event::PlayerMoved {
+                    player,
+                    movement: simulation::PlayerMove::Teleport(..),
+                    ..
+                } => interesting_events.send(InterestingEvent::PlayerTeleported(*player)),
+                simulation::Event::ObstacleDestroyed { source, kind, .. } => {
+                    interesting_events.send(match source.player() {
+                        Some(player) => InterestingEvent::ObstacleDestroyedByPlayer(*player),
+                        None => InterestingEvent::ObstacleDestroyedByLaser,
+                    });
+                    if *kind == simulation::ObstacleKind::Barrel {
+                        interesting_events.send(InterestingEvent::BarrelExploded);
+                    }
+                }
+                _ => (),
+            }
+            simulation_events.send(event);
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Animation {
     Walk,
@@ -374,54 +600,27 @@ fn walk_animate_sprite_system(
     }
 }
 
-fn clear_moving_marker(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut game: ResMut<Game>,
-    mut moving: Mut<PlayerMoving>,
-    entity: Entity,
-) {
-    moving.timer_before_allow_new_move.tick(time.delta_seconds);
-    moving.timer_before_update_position.tick(time.delta_seconds);
-    if moving.timer_before_allow_new_move.just_finished {
-        commands.remove_one::<PlayerMoving>(entity);
-    }
-    if moving.timer_before_update_position.just_finished {
-        game.player.x = moving.to.0;
-        game.player.y = moving.to.1;
-    }
-}
-
-#[derive(PartialEq, Clone, Copy)]
-pub enum CauseOfDeath {
-    Laser,
-    LaserFire,
-    BombFire,
-}
-
-impl std::fmt::Display for CauseOfDeath {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            CauseOfDeath::Laser => write!(f, "laser"),
-            CauseOfDeath::LaserFire => write!(f, "laser trail"),
-            CauseOfDeath::BombFire => write!(f, "your own bomb"),
-        }
-    }
-}
-
 #[derive(PartialEq)]
 pub enum GameEvents {
     NewRound,
-    Lost(CauseOfDeath),
+    /// A player was burned, in co-op they can still be revived
+    Lost(usize, CauseOfDeath),
+    PlayerRevived(usize),
+    GameOver,
     Pause,
     NewHighscore,
     NewHighround,
-    PlayerBonus,
+    PlayerBonus(usize),
 }
 
 pub enum InterestingEvent {
-    BombPlaced,
-    ObstacleDestroyedByPlayer,
+    BombPlaced(usize),
+    BombExploded(usize),
+    ObstacleDestroyedByPlayer(usize),
     ObstacleDestroyedByLaser,
-    BombChainDetonated,
+    BarrelExploded,
+    BombChainDetonated(usize),
+    BombKicked(usize),
+    ShieldUsed(usize),
+    PlayerTeleported(usize),
 }
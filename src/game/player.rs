@@ -0,0 +1,125 @@
+use super::*;
+
+use simulation::PlayerMove;
+
+pub fn move_player(
+    mut commands: Commands,
+    game: Res<Game>,
+    layout: Res<Layout>,
+    (mut event_reader, events): (
+        Local<EventReader<simulation::Event>>,
+        Res<Events<simulation::Event>>,
+    ),
+    player_query: Query<(Entity, &PlayerComponent, &Transform)>,
+    chained_eased_query: Query<&bevy_easings::EasingChainComponent<Transform>>,
+) {
+    let ratio = layout.ratio;
+    for event in event_reader.iter(&events) {
+        if let simulation::Event::PlayerMoved {
+            player,
+            from,
+            movement,
+            duration,
+        } = event
+        {
+            let move_delay = *duration;
+            let scale = match game.sim.players()[*player].direction {
+                FacingDirection::Right => Vec3::new(1., 1., 1.),
+                FacingDirection::Left => Vec3::new(-1., 1., 1.),
+            };
+            let base_transform = Transform {
+                translation: Vec3::new(
+                    layout.x_to(from.0 as i32),
+                    layout.y_to(from.1 as i32),
+                    Z_PLAYER,
+                ),
+                scale,
+                ..Default::default()
+            };
+
+            for (entity, _, transform) in player_query
+                .iter()
+                .filter(|(_, moved, _)| moved.0 == *player)
+            {
+                if chained_eased_query
+                    .get_component::<bevy_easings::EasingChainComponent<Transform>>(entity)
+                    .is_ok()
+                {
+                    commands.remove_one::<bevy_easings::EasingChainComponent<Transform>>(entity);
+                }
+
+                match *movement {
+                    PlayerMove::Walk(_, x, y) => {
+                        commands.insert_one(
+                            entity,
+                            transform.ease_to(
+                                Transform {
+                                    translation: Vec3::new(
+                                        layout.x_to(x as i32),
+                                        layout.y_to(y as i32),
+                                        Z_PLAYER,
+                                    ),
+                                    scale,
+                                    ..Default::default()
+                                },
+                                bevy_easings::EaseMethod::Linear,
+                                bevy_easings::EasingType::Once {
+                                    duration: std::time::Duration::from_millis(move_delay),
+                                },
+                            ),
+                        );
+                    }
+                    PlayerMove::Teleport(x, y) => {
+                        commands.insert_one(
+                            entity,
+                            transform.ease_to(
+                                Transform {
+                                    translation: Vec3::new(
+                                        layout.x_to(x as i32),
+                                        layout.y_to(y as i32),
+                                        Z_PLAYER,
+                                    ),
+                                    ..base_transform
+                                },
+                                bevy_easings::EaseMethod::Discrete,
+                                bevy_easings::EasingType::Once {
+                                    duration: std::time::Duration::from_millis(move_delay),
+                                },
+                            ),
+                        );
+                    }
+                    PlayerMove::Bump(direction) => {
+                        let change = direction.change();
+                        commands.insert_one(
+                            entity,
+                            transform
+                                .ease_to(
+                                    Transform {
+                                        translation: Vec3::new(
+                                            layout.x_to(from.0 as i32)
+                                                + change.0 as f32 * 0.65 * ratio * TILE_SIZE / 2.,
+                                            layout.y_to(from.1 as i32)
+                                                + change.1 as f32 * 0.65 * ratio * TILE_SIZE / 2.,
+                                            Z_PLAYER,
+                                        ),
+                                        ..base_transform
+                                    },
+                                    bevy_easings::EaseFunction::QuadraticIn,
+                                    bevy_easings::EasingType::Once {
+                                        duration: std::time::Duration::from_millis(move_delay / 4),
+                                    },
+                                )
+                                .ease_to(
+                                    base_transform,
+                                    bevy_easings::EaseFunction::QuadraticOut,
+                                    bevy_easings::EasingType::Once {
+                                        duration: std::time::Duration::from_millis(move_delay / 4),
+                                    },
+                                ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
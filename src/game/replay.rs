@@ -0,0 +1,33 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use simulation::{Replay, Simulation};
+use tracing::{info, warn};
+
+const REPLAY_FOLDER: &str = "replays";
+
+/// When set, games play this replay instead of listening to the keyboard.
+#[derive(Default)]
+pub struct ReplayMode(pub Option<Replay>);
+
+/// Seconds since the epoch, to name files saved after a game
+pub(super) fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn save_replay(sim: &Simulation) {
+    if sim.is_replay() {
+        return;
+    }
+    let path = format!("{}/{}-{}.replay", REPLAY_FOLDER, timestamp(), sim.seed());
+    if let Err(err) = std::fs::create_dir_all(REPLAY_FOLDER) {
+        warn!("error creating folder {}: {}", REPLAY_FOLDER, err);
+        return;
+    }
+    match sim.replay().save(&path) {
+        Ok(()) => info!("replay saved to {}", path),
+        Err(err) => warn!("error saving replay to {}: {}", path, err),
+    }
+}
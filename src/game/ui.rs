@@ -1,13 +1,4 @@
 use super::*;
-use rand::prelude::IteratorRandom;
-
-#[derive(Debug, Clone, PartialEq, Eq, enum_utils::IterVariants)]
-enum LaserPowerUp {
-    Speed,
-    ObstacleSpawnDelay,
-    ObstacleSpawnCount,
-    ObstacleStrengh,
-}
 
 #[derive(Clone, Copy)]
 pub enum PauseButton {
@@ -41,7 +32,8 @@ pub fn ui_event_update(
     mut buttons: ResMut<Assets<crate::ui::button::Button>>,
     mut round_text: Query<(&mut Text, &UiComponent, &Parent)>,
     is_new_best: Query<&Stared>,
-    mut player_query: Query<With<PlayerComponent, (Entity, &mut Transform)>>,
+    layout: Res<Layout>,
+    mut player_query: Query<(Entity, &PlayerComponent, &mut Transform)>,
 ) {
     let transparent_background = materials.add(Color::NONE.into());
     let menu_indicator: Handle<ColorMaterial> =
@@ -50,11 +42,15 @@ pub fn ui_event_update(
     for event in event_reader.iter(&events) {
         match event {
             GameEvents::NewRound => {
-                game.round += 1;
                 for (mut text, component, parent) in round_text.iter_mut() {
                     if *component == UiComponent::Round {
-                        text.value = format!("Round {}", game.round);
-                        if screen.is_new_highround(game.round) {
+                        text.value = format!("Round {}", game.sim.round());
+                        if game.sim.mode() == Mode::Solo
+                            && game.sim.arena().is_none()
+                            && !game.sim.is_replay()
+                            && !screen.demo
+                            && screen.is_new_highround(game.sim.round())
+                        {
                             if is_new_best.get_component::<Stared>(parent.0).is_err() {
                                 text.style.color = crate::ui::ColorScheme::TEXT_HIGHLIGHT;
                                 commands.spawn(ImageComponents {
@@ -76,40 +72,24 @@ pub fn ui_event_update(
                         }
                     }
                 }
-                let mut rng = rand::thread_rng();
-                match if game.round == 1 {
-                    // avoid power up ObstacleStrengh on first round
-                    LaserPowerUp::iter()
-                        .filter(|bonus| *bonus != LaserPowerUp::ObstacleStrengh)
-                        .choose(&mut rng)
-                        .unwrap()
-                } else {
-                    LaserPowerUp::iter().choose(&mut rng).unwrap()
-                } {
-                    LaserPowerUp::Speed => {
-                        game.laser.speed =
-                            (game.laser.speed as f64 * crate::CONFIG.laser_powerup_speed) as u64
-                    }
-                    LaserPowerUp::ObstacleSpawnDelay => {
-                        game.laser.spawn_obstacles_delay = (game.laser.spawn_obstacles_delay as f32
-                            * crate::CONFIG.laser_powerup_obstacle_delay)
-                            as u16
-                    }
-                    LaserPowerUp::ObstacleSpawnCount => {
-                        game.laser.nb_obstacles += crate::CONFIG.laser_powerup_nb_obstacles
-                    }
-                    LaserPowerUp::ObstacleStrengh => {
-                        game.laser.obstacle_strength +=
-                            crate::CONFIG.laser_powerup_obstacle_strength
-                    }
-                }
             }
-            GameEvents::Lost(_) => {
-                commands.spawn((DeathAnimation(Timer::from_seconds(2., false)), ScreenTag));
-                for (player_entity, mut transform) in player_query.iter_mut() {
+            GameEvents::Lost(player, _) => {
+                let (x, y) = {
+                    let down = &game.sim.players()[*player];
+                    (down.x, down.y)
+                };
+                for (player_entity, _, mut transform) in player_query
+                    .iter_mut()
+                    .filter(|(_, down, _)| down.0 == *player)
+                {
                     commands.remove_one::<bevy_easings::EasingComponent<Transform>>(player_entity);
+                    transform.translation =
+                        Vec3::new(layout.x_to(x as i32), layout.y_to(y as i32), Z_PLAYER);
                     transform.scale = Vec3::new(1., 1., 1.);
                 }
+            }
+            GameEvents::GameOver => {
+                commands.spawn((DeathAnimation(Timer::from_seconds(2., false)), ScreenTag));
                 game.state = GameState::Death;
             }
             GameEvents::Pause => {
@@ -301,7 +281,8 @@ pub fn ui_event_update(
             }
             GameEvents::NewHighround => {}
             GameEvents::NewHighscore => {}
-            GameEvents::PlayerBonus => {}
+            GameEvents::PlayerBonus(_) => {}
+            GameEvents::PlayerRevived(_) => {}
         }
     }
 }
@@ -311,27 +292,37 @@ pub struct DeathAnimation(Timer);
 pub fn death_animation(
     mut game_screen: ResMut<crate::GameScreen>,
     time: Res<Time>,
-    mut animation_query: Query<&mut Animation>,
+    game: Res<Game>,
+    mut animation_query: Query<(&mut Animation, &PlayerSprite)>,
     mut death_query: Query<&mut DeathAnimation>,
 ) {
     for mut death in death_query.iter_mut() {
         death.0.tick(time.delta_seconds);
         if death.0.just_finished {
-            game_screen.current_screen = crate::Screen::Lost;
-        } else {
-            for mut animation in animation_query.iter_mut() {
-                if *animation != Animation::Die {
-                    *animation = Animation::Die;
-                }
+            if game_screen.demo {
+                game_screen.demo = false;
+                game_screen.current_screen = crate::Screen::Menu;
+            } else {
+                game_screen.current_screen = crate::Screen::Lost;
             }
         }
     }
+    // downed players in co-op get back to walking once revived
+    for (mut animation, player) in animation_query.iter_mut() {
+        let expected = match game.sim.players().get(player.0) {
+            Some(player) if !player.is_alive() => Animation::Die,
+            _ => Animation::Walk,
+        };
+        if *animation != expected {
+            *animation = expected;
+        }
+    }
 }
 
 pub fn score(
     mut commands: Commands,
     screen: Res<crate::GameScreen>,
-    mut game: ResMut<Game>,
+    game: Res<Game>,
     mut game_events: ResMut<Events<GameEvents>>,
     asset_handles: Res<crate::AssetHandles>,
     mut score_text: Query<(&mut Text, &UiComponent, &Timer, &Parent)>,
@@ -340,9 +331,25 @@ pub fn score(
     if game.state == GameState::Play {
         for (mut score, ui, timer, parent) in score_text.iter_mut() {
             if *ui == UiComponent::Score && timer.just_finished {
-                score.value = format!("{}", game.score);
-                game.score += game.round as u32;
-                if screen.is_new_highscore(game.score) {
+                score.value = if game.sim.mode() == Mode::Versus {
+                    game.sim
+                        .players()
+                        .iter()
+                        .map(|player| format!("{}", player.score))
+                        .collect::<Vec<_>>()
+                        .join(" - ")
+                } else {
+                    format!("{}", game.sim.score())
+                };
+                // only games that make it to the high scores get a star
+                if game.sim.mode() != Mode::Solo
+                    || game.sim.arena().is_some()
+                    || game.sim.is_replay()
+                    || screen.demo
+                {
+                    continue;
+                }
+                if screen.is_new_highscore(game.sim.score()) {
                     if is_new_best.get_component::<Stared>(parent.0).is_err() {
                         score.style.color = crate::ui::ColorScheme::TEXT_HIGHLIGHT;
                         commands.spawn(ImageComponents {
@@ -371,24 +378,45 @@ pub fn score(
 pub enum UiComponent {
     Round,
     Score,
-    BombsAvailable,
+    BombsAvailable(usize),
+}
+
+/// Power ups that last, shown under the bombs of the player who took them.
+const LASTING_POWERUPS: [PlayerPowerUp; 5] = [
+    PlayerPowerUp::Kick,
+    PlayerPowerUp::Remote,
+    PlayerPowerUp::Shield,
+    PlayerPowerUp::PlayerSpeed,
+    PlayerPowerUp::Pierce,
+];
+
+pub struct PowerUpIcon {
+    player: usize,
+    powerup: PlayerPowerUp,
 }
 
 pub fn setup(
     mut commands: Commands,
     game_screen: Res<crate::GameScreen>,
+    game: Res<Game>,
     mut asset_handles: ResMut<crate::AssetHandles>,
     screen: Res<Screen>,
     asset_server: Res<AssetServer>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    (layout, wnds): (Res<Layout>, Res<Windows>),
 ) {
     if game_screen.current_screen == CURRENT_SCREEN && !screen.loaded {
         info!("Loading screen (ui)");
+        let window = wnds.get_primary().unwrap();
         let font: Handle<Font> = asset_handles.get_font_main_handle(&asset_server);
         let transparent_background = materials.add(Color::NONE.into());
-        let bomb_icon_handle = asset_handles
-            .get_board_handles(&asset_server, &mut materials)
-            .bomb_icon;
+        let board_assets = asset_handles.get_board_handles(&asset_server, &mut materials);
+        let bomb_icon_handle = board_assets.bomb_icon.clone();
+        let round_position = Rect {
+            left: Val::Px(4.),
+            top: Val::Px(4.),
+            ..Default::default()
+        };
         commands
             .spawn(NodeComponents {
                 style: Style {
@@ -397,16 +425,13 @@ pub fn setup(
                         ..Default::default()
                     },
                     position_type: PositionType::Absolute,
-                    position: Rect {
-                        left: Val::Px(4.),
-                        top: Val::Px(4.),
-                        ..Default::default()
-                    },
+                    position: layout.hud_position(window, &round_position),
                     ..Default::default()
                 },
                 material: transparent_background.clone(),
                 ..Default::default()
             })
+            .with(Hud(round_position))
             .with(ScreenTag)
             .with_children(|round_parent| {
                 round_parent
@@ -431,6 +456,42 @@ pub fn setup(
                     .with(UiComponent::Round);
             });
 
+        if game_screen.demo {
+            let demo_position = Rect {
+                left: Val::Px(4.),
+                bottom: Val::Px(4.),
+                ..Default::default()
+            };
+            commands
+                .spawn(TextComponents {
+                    style: Style {
+                        size: Size {
+                            height: Val::Px(20.),
+                            ..Default::default()
+                        },
+                        position_type: PositionType::Absolute,
+                        position: layout.hud_position(window, &demo_position),
+                        ..Default::default()
+                    },
+                    text: Text {
+                        value: "demo - press any key".to_string(),
+                        font: asset_handles.get_font_sub_handle(&asset_server),
+                        style: TextStyle {
+                            color: crate::ui::ColorScheme::TEXT_DIM,
+                            font_size: 20.,
+                        },
+                    },
+                    ..Default::default()
+                })
+                .with(Hud(demo_position))
+                .with(ScreenTag);
+        }
+
+        let score_position = Rect {
+            right: Val::Px(3.),
+            top: Val::Px(3.),
+            ..Default::default()
+        };
         commands
             .spawn(NodeComponents {
                 style: Style {
@@ -439,17 +500,14 @@ pub fn setup(
                         ..Default::default()
                     },
                     position_type: PositionType::Absolute,
-                    position: Rect {
-                        right: Val::Px(3.),
-                        top: Val::Px(3.),
-                        ..Default::default()
-                    },
+                    position: layout.hud_position(window, &score_position),
                     flex_direction: FlexDirection::RowReverse,
                     ..Default::default()
                 },
                 material: transparent_background.clone(),
                 ..Default::default()
             })
+            .with(Hud(score_position))
             .with(ScreenTag)
             .with_children(|score_parent| {
                 score_parent
@@ -475,45 +533,122 @@ pub fn setup(
                     .with(Timer::from_seconds(0.1, true));
             });
 
-        commands
-            .spawn(NodeComponents {
-                style: Style {
-                    size: Size {
-                        height: Val::Px(30. / 2.),
-                        ..Default::default()
-                    },
-                    position_type: PositionType::Absolute,
-                    position: Rect {
-                        right: Val::Px(10. / 4.),
-                        top: Val::Px(130. / 4.),
+        for player in 0..game.sim.players().len() {
+            let bombs_position = Rect {
+                right: Val::Px(10. / 4.),
+                top: Val::Px(130. / 4. + player as f32 * 20.),
+                ..Default::default()
+            };
+            commands
+                .spawn(NodeComponents {
+                    style: Style {
+                        size: Size {
+                            height: Val::Px(30. / 2.),
+                            ..Default::default()
+                        },
+                        position_type: PositionType::Absolute,
+                        position: layout.hud_position(window, &bombs_position),
+                        flex_direction: FlexDirection::RowReverse,
                         ..Default::default()
                     },
-                    flex_direction: FlexDirection::RowReverse,
+                    material: transparent_background.clone(),
                     ..Default::default()
-                },
-                material: transparent_background.clone(),
+                })
+                .with(Hud(bombs_position))
+                .with(UiComponent::BombsAvailable(player))
+                .with_children(|p| {
+                    p.spawn(ImageComponents {
+                        style: Style {
+                            size: Size {
+                                height: Val::Px(30. / 2.),
+                                width: Val::Px(30. / 2.),
+                            },
+                            margin: Rect {
+                                left: Val::Px(10. / 2.),
+                                right: Val::Px(10. / 2.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        material: bomb_icon_handle.clone(),
+                        ..Default::default()
+                    });
+                })
+                .with(ScreenTag);
+        }
+
+        let nb_players = game.sim.players().len();
+        for player in 0..nb_players {
+            let powerups_position = Rect {
+                right: Val::Px(10. / 4.),
+                top: Val::Px(130. / 4. + (nb_players + player) as f32 * 20.),
                 ..Default::default()
-            })
-            .with(UiComponent::BombsAvailable)
-            .with_children(|p| {
-                p.spawn(ImageComponents {
+            };
+            commands
+                .spawn(NodeComponents {
                     style: Style {
                         size: Size {
                             height: Val::Px(30. / 2.),
-                            width: Val::Px(30. / 2.),
-                        },
-                        margin: Rect {
-                            left: Val::Px(10. / 2.),
-                            right: Val::Px(10. / 2.),
                             ..Default::default()
                         },
+                        position_type: PositionType::Absolute,
+                        position: layout.hud_position(window, &powerups_position),
+                        flex_direction: FlexDirection::RowReverse,
                         ..Default::default()
                     },
-                    material: bomb_icon_handle,
+                    material: transparent_background.clone(),
                     ..Default::default()
-                });
+                })
+                .with(Hud(powerups_position))
+                .with_children(|p| {
+                    for powerup in LASTING_POWERUPS.iter() {
+                        p.spawn(ImageComponents {
+                            style: Style {
+                                display: Display::None,
+                                size: Size {
+                                    height: Val::Px(30. / 2.),
+                                    width: Val::Px(30. / 2.),
+                                },
+                                margin: Rect {
+                                    left: Val::Px(10. / 2.),
+                                    right: Val::Px(10. / 2.),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            },
+                            material: board_assets.powerup(*powerup),
+                            ..Default::default()
+                        })
+                        .with(PowerUpIcon {
+                            player,
+                            powerup: *powerup,
+                        });
+                    }
+                })
+                .with(ScreenTag);
+        }
+    }
+}
+
+pub fn display_lasting_powerups(game: Res<Game>, mut icons: Query<(&PowerUpIcon, &mut Style)>) {
+    for (icon, mut style) in icons.iter_mut() {
+        let active = game
+            .sim
+            .players()
+            .get(icon.player)
+            .map(|player| match icon.powerup {
+                PlayerPowerUp::Kick => player.kick,
+                PlayerPowerUp::Remote => player.remote,
+                PlayerPowerUp::Shield => player.shield,
+                PlayerPowerUp::PlayerSpeed => player.speed < game.sim.config().player_speed,
+                PlayerPowerUp::Pierce => player.pierce,
+                _ => false,
             })
-            .with(ScreenTag);
+            .unwrap_or(false);
+        let display = if active { Display::Flex } else { Display::None };
+        if style.display != display {
+            style.display = display;
+        }
     }
 }
 
@@ -523,21 +658,20 @@ pub fn display_bombs_available(
     mut asset_handles: ResMut<crate::AssetHandles>,
     asset_server: Res<AssetServer>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    used_bombs: Query<&BombComponent>,
     mut parent_component: Query<(Entity, &mut Children, &UiComponent)>,
 ) {
-    let used_bombs = used_bombs.iter().count();
     let bomb_icon_handle = asset_handles
         .get_board_handles(&asset_server, &mut materials)
         .bomb_icon;
     for (entity, mut children, component) in parent_component.iter_mut() {
-        if *component == UiComponent::BombsAvailable {
-            if children.0.len() != game.player.nb_bombs - used_bombs {
+        if let UiComponent::BombsAvailable(player) = *component {
+            let bombs_available = game.sim.bombs_available(player);
+            if children.0.len() != bombs_available {
                 for _ in 0..children.0.len() {
                     let child = children.pop().unwrap();
                     commands.despawn(child);
                 }
-                for _ in 0..(game.player.nb_bombs - used_bombs) {
+                for _ in 0..bombs_available {
                     commands.spawn(ImageComponents {
                         style: Style {
                             size: Size {
@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use bevy::{
+    input::gamepad::{
+        Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, GamepadEvent,
+        GamepadEventType,
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::bindings::Action;
+
+/// Gamepad options as written in `settings.conf`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct GamepadSettings {
+    /// stick position under which it is considered centered
+    pub dead_zone: f32,
+    /// seconds a direction is held before it starts repeating in menus
+    pub repeat_delay: f32,
+    /// seconds between two repeats
+    pub repeat_interval: f32,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        GamepadSettings {
+            dead_zone: 0.3,
+            repeat_delay: 0.4,
+            repeat_interval: 0.12,
+        }
+    }
+}
+
+const BUTTONS: &[(GamepadButtonType, Action)] = &[
+    (GamepadButtonType::DPadUp, Action::Up),
+    (GamepadButtonType::DPadDown, Action::Down),
+    (GamepadButtonType::DPadLeft, Action::Left),
+    (GamepadButtonType::DPadRight, Action::Right),
+    (GamepadButtonType::South, Action::Bomb),
+    (GamepadButtonType::West, Action::Detonate),
+    (GamepadButtonType::South, Action::Confirm),
+    (GamepadButtonType::East, Action::Back),
+    (GamepadButtonType::Start, Action::Pause),
+    (GamepadButtonType::North, Action::HighScores),
+];
+
+const REPEATING: &[Action] = &[Action::Up, Action::Down, Action::Left, Action::Right];
+
+#[derive(Debug)]
+struct Pad {
+    gamepad: Gamepad,
+    pressed: Vec<Action>,
+    just_pressed: Vec<Action>,
+    next_repeat: HashMap<Action, f32>,
+}
+
+impl Pad {
+    fn new(gamepad: Gamepad) -> Self {
+        Pad {
+            gamepad,
+            pressed: vec![],
+            just_pressed: vec![],
+            next_repeat: HashMap::new(),
+        }
+    }
+
+    /// Update the actions of the pad with the ones now held, `delta_seconds` after the last
+    /// update
+    fn update(&mut self, pressed: Vec<Action>, delta_seconds: f32, settings: &GamepadSettings) {
+        let mut just_pressed = vec![];
+        for action in &pressed {
+            if !self.pressed.contains(action) {
+                just_pressed.push(*action);
+                if REPEATING.contains(action) {
+                    self.next_repeat.insert(*action, settings.repeat_delay);
+                }
+            } else if let Some(next_repeat) = self.next_repeat.get_mut(action) {
+                *next_repeat -= delta_seconds;
+                if *next_repeat <= 0. {
+                    just_pressed.push(*action);
+                    *next_repeat += settings.repeat_interval;
+                }
+            }
+        }
+        self.next_repeat
+            .retain(|action, _| pressed.contains(action));
+
+        self.pressed = pressed;
+        self.just_pressed = just_pressed;
+    }
+}
+
+/// Actions triggered by the connected gamepads, updated before every frame
+#[derive(Debug, Default)]
+pub struct GamepadActions {
+    settings: GamepadSettings,
+    pads: Vec<Pad>,
+}
+
+impl GamepadActions {
+    pub fn new(settings: &GamepadSettings) -> Self {
+        GamepadActions {
+            settings: settings.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Is `action` currently held on any gamepad
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pads.iter().any(|pad| pad.pressed.contains(&action))
+    }
+
+    /// Was `action` pressed this frame on any gamepad, or repeated for a held direction
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.pads
+            .iter()
+            .any(|pad| pad.just_pressed.contains(&action))
+    }
+
+    /// Is `action` currently held on the `index`th connected gamepad
+    pub fn pressed_on(&self, index: usize, action: Action) -> bool {
+        self.pads
+            .get(index)
+            .map(|pad| pad.pressed.contains(&action))
+            .unwrap_or(false)
+    }
+
+    /// Was `action` pressed this frame on the `index`th connected gamepad
+    pub fn just_pressed_on(&self, index: usize, action: Action) -> bool {
+        self.pads
+            .get(index)
+            .map(|pad| pad.just_pressed.contains(&action))
+            .unwrap_or(false)
+    }
+
+    pub fn nb_connected(&self) -> usize {
+        self.pads.len()
+    }
+}
+
+pub struct Plugin;
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_to_stage(bevy::app::stage::PRE_UPDATE, connection_system.system())
+            .add_system_to_stage(bevy::app::stage::PRE_UPDATE, actions_system.system());
+    }
+}
+
+fn connection_system(
+    mut gamepad_actions: ResMut<GamepadActions>,
+    mut event_reader: Local<EventReader<GamepadEvent>>,
+    gamepad_events: Res<Events<GamepadEvent>>,
+) {
+    for event in event_reader.iter(&gamepad_events) {
+        match event {
+            GamepadEvent(gamepad, GamepadEventType::Connected) => {
+                info!("gamepad {:?} connected", gamepad);
+                gamepad_actions.pads.push(Pad::new(*gamepad));
+            }
+            GamepadEvent(gamepad, GamepadEventType::Disconnected) => {
+                info!("gamepad {:?} disconnected", gamepad);
+                gamepad_actions.pads.retain(|pad| pad.gamepad != *gamepad);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Actions held on `gamepad` by its buttons and sticks, each only once
+fn held_actions(
+    gamepad: Gamepad,
+    buttons: &Input<GamepadButton>,
+    axes: &Axis<GamepadAxis>,
+    dead_zone: f32,
+) -> Vec<Action> {
+    let mut pressed = vec![];
+    for (button, action) in BUTTONS {
+        if buttons.pressed(GamepadButton(gamepad, *button)) {
+            pressed.push(*action);
+        }
+    }
+    let axis = |axis_type| axes.get(&GamepadAxis(gamepad, axis_type)).unwrap_or(0.);
+    for (x, y) in &[
+        (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY),
+        (GamepadAxisType::DPadX, GamepadAxisType::DPadY),
+    ] {
+        let (x, y) = (axis(*x), axis(*y));
+        if x < -dead_zone {
+            pressed.push(Action::Left);
+        }
+        if x > dead_zone {
+            pressed.push(Action::Right);
+        }
+        if y > dead_zone {
+            pressed.push(Action::Up);
+        }
+        if y < -dead_zone {
+            pressed.push(Action::Down);
+        }
+    }
+    pressed.sort_by_key(|action| *action as u8);
+    pressed.dedup();
+    pressed
+}
+
+fn actions_system(
+    time: Res<Time>,
+    mut gamepad_actions: ResMut<GamepadActions>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+) {
+    let gamepad_actions = &mut *gamepad_actions;
+    let settings = &gamepad_actions.settings;
+
+    for pad in gamepad_actions.pads.iter_mut() {
+        let pressed = held_actions(pad.gamepad, &buttons, &axes, settings.dead_zone);
+        pad.update(pressed, time.delta_seconds, settings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAMEPAD: Gamepad = Gamepad(0);
+
+    fn settings() -> GamepadSettings {
+        // durations that add up exactly as floats
+        GamepadSettings {
+            dead_zone: 0.3,
+            repeat_delay: 0.5,
+            repeat_interval: 0.25,
+        }
+    }
+
+    fn press(buttons: &[GamepadButtonType]) -> Input<GamepadButton> {
+        let mut input = Input::default();
+        for button in buttons {
+            input.press(GamepadButton(GAMEPAD, *button));
+        }
+        input
+    }
+
+    fn stick(x: f32, y: f32) -> Axis<GamepadAxis> {
+        let mut axes = Axis::default();
+        axes.set(GamepadAxis(GAMEPAD, GamepadAxisType::LeftStickX), x);
+        axes.set(GamepadAxis(GAMEPAD, GamepadAxisType::LeftStickY), y);
+        axes
+    }
+
+    #[test]
+    fn actions_are_just_pressed_on_the_first_frame_only() {
+        let settings = settings();
+        let mut pad = Pad::new(GAMEPAD);
+
+        pad.update(vec![Action::Bomb], 0.1, &settings);
+        assert_eq!(pad.just_pressed, vec![Action::Bomb]);
+        pad.update(vec![Action::Bomb], 0.1, &settings);
+        assert_eq!(pad.pressed, vec![Action::Bomb]);
+        assert!(pad.just_pressed.is_empty());
+
+        pad.update(vec![], 0.1, &settings);
+        assert!(pad.pressed.is_empty());
+        pad.update(vec![Action::Bomb], 0.1, &settings);
+        assert_eq!(pad.just_pressed, vec![Action::Bomb]);
+    }
+
+    #[test]
+    fn sticks_inside_the_dead_zone_are_centered() {
+        let buttons = press(&[]);
+        let dead_zone = settings().dead_zone;
+
+        assert!(held_actions(GAMEPAD, &buttons, &stick(0.2, -0.2), dead_zone).is_empty());
+        assert_eq!(
+            held_actions(GAMEPAD, &buttons, &stick(0.5, 0.), dead_zone),
+            vec![Action::Right]
+        );
+        assert_eq!(
+            held_actions(GAMEPAD, &buttons, &stick(-0.5, 0.5), dead_zone),
+            vec![Action::Up, Action::Left]
+        );
+    }
+
+    #[test]
+    fn held_directions_repeat_after_a_delay() {
+        let settings = settings();
+        let mut pad = Pad::new(GAMEPAD);
+        let repeated = |pad: &mut Pad, delta| {
+            pad.update(vec![Action::Up, Action::Bomb], delta, &settings);
+            pad.just_pressed.clone()
+        };
+
+        assert_eq!(repeated(&mut pad, 0.), vec![Action::Up, Action::Bomb]);
+        assert!(repeated(&mut pad, 0.25).is_empty());
+        // the delay is over
+        assert_eq!(repeated(&mut pad, 0.25), vec![Action::Up]);
+        assert!(repeated(&mut pad, 0.125).is_empty());
+        // then once every interval, while other actions never repeat
+        assert_eq!(repeated(&mut pad, 0.125), vec![Action::Up]);
+        assert_eq!(repeated(&mut pad, 0.25), vec![Action::Up]);
+    }
+
+    #[test]
+    fn actions_held_in_several_ways_are_counted_once() {
+        let buttons = press(&[GamepadButtonType::DPadUp, GamepadButtonType::South]);
+        let mut axes = stick(0., 1.);
+        axes.set(GamepadAxis(GAMEPAD, GamepadAxisType::DPadY), 1.);
+
+        assert_eq!(
+            held_actions(GAMEPAD, &buttons, &axes, settings().dead_zone),
+            vec![Action::Up, Action::Bomb, Action::Confirm]
+        );
+    }
+}
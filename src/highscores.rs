@@ -0,0 +1,384 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::game::CauseOfDeath;
+use simulation::Difficulty;
+
+const CURRENT_SCREEN: crate::Screen = crate::Screen::HighScores;
+
+const TOP_N: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Run {
+    pub score: u32,
+    pub round: u16,
+    pub cause_of_death: Option<CauseOfDeath>,
+    pub seed: u64,
+    pub date: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct HighScoreTable {
+    pub highscore: u32,
+    pub highround: u16,
+    pub runs: Vec<Run>,
+}
+
+impl HighScoreTable {
+    fn add(&mut self, run: Run) {
+        self.highscore = u32::max(self.highscore, run.score);
+        self.highround = u16::max(self.highround, run.round);
+        self.runs.push(run);
+        self.runs
+            .sort_by(|a, b| b.score.cmp(&a.score).then(b.round.cmp(&a.round)));
+        self.runs.truncate(TOP_N);
+    }
+}
+
+/// One table per difficulty so that results are comparable
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct HighScores {
+    easy: HighScoreTable,
+    normal: HighScoreTable,
+    hard: HighScoreTable,
+    insane: HighScoreTable,
+}
+
+fn path() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join("kmanb"))
+        .unwrap_or_default()
+        .join("highscores.conf")
+}
+
+impl HighScores {
+    pub fn load() -> Self {
+        config::read_from(path()).unwrap_or_else(|err| {
+            warn!("error reading high scores: {}", err);
+            HighScores::default()
+        })
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = config::write_to(path(), self) {
+            warn!("error saving high scores: {}", err);
+        }
+    }
+
+    pub fn table(&self, difficulty: Difficulty) -> &HighScoreTable {
+        match difficulty {
+            Difficulty::Easy => &self.easy,
+            Difficulty::Normal => &self.normal,
+            Difficulty::Hard => &self.hard,
+            Difficulty::Insane => &self.insane,
+        }
+    }
+
+    pub fn add(&mut self, difficulty: Difficulty, run: Run) {
+        match difficulty {
+            Difficulty::Easy => self.easy.add(run),
+            Difficulty::Normal => self.normal.add(run),
+            Difficulty::Hard => self.hard.add(run),
+            Difficulty::Insane => self.insane.add(run),
+        }
+    }
+}
+
+struct ScreenTag;
+
+struct Screen {
+    loaded: bool,
+    reload: bool,
+    difficulty: Difficulty,
+}
+impl Default for Screen {
+    fn default() -> Self {
+        Screen {
+            loaded: false,
+            reload: false,
+            difficulty: Difficulty::default(),
+        }
+    }
+}
+
+pub struct Plugin;
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_resource(Screen::default())
+            .add_system(input_system.system())
+            .add_system(setup.system())
+            .add_system_to_stage(crate::custom_stage::TEAR_DOWN, tear_down.system());
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    game_screen: Res<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    highscores: Res<HighScores>,
+    config: Res<simulation::Config>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut asset_handles: ResMut<crate::AssetHandles>,
+) {
+    if game_screen.current_screen == CURRENT_SCREEN && !screen.loaded {
+        info!("Loading screen");
+
+        if !screen.reload {
+            screen.difficulty = game_screen.difficulty;
+        }
+        let table = highscores.table(screen.difficulty);
+
+        let color_none = materials.add(Color::NONE.into());
+
+        let font: Handle<Font> = asset_handles.get_font_main_handle(&asset_server);
+
+        let font_sub: Handle<Font> = asset_handles.get_font_sub_handle(&asset_server);
+
+        let medals = asset_handles.get_medal_handles(&asset_server, &mut materials);
+
+        commands
+            .spawn(NodeComponents {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect::<Val> {
+                        left: Val::Percent(20.),
+                        right: Val::Undefined,
+                        bottom: Val::Undefined,
+                        top: Val::Percent(15.),
+                    },
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..Default::default()
+                },
+                material: color_none.clone(),
+                ..Default::default()
+            })
+            .with(ScreenTag)
+            .with_children(|parent| {
+                parent.spawn(TextComponents {
+                    style: Style {
+                        size: Size {
+                            height: Val::Px(150. / 2.),
+                            ..Default::default()
+                        },
+                        margin: Rect {
+                            bottom: Val::Px(30.),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    text: Text {
+                        value: format!("High Scores - {}", screen.difficulty),
+                        font,
+                        style: TextStyle {
+                            color: crate::ui::ColorScheme::TEXT,
+                            font_size: 150.0 / 2.,
+                        },
+                    },
+                    ..Default::default()
+                });
+
+                parent.spawn(TextComponents {
+                    style: Style {
+                        size: Size {
+                            height: Val::Px(20.),
+                            ..Default::default()
+                        },
+                        margin: Rect {
+                            bottom: Val::Px(30.),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    text: Text {
+                        value: "left and right to change difficulty".to_string(),
+                        font: font_sub.clone(),
+                        style: TextStyle {
+                            color: crate::ui::ColorScheme::TEXT_DIM,
+                            font_size: 20.,
+                        },
+                    },
+                    ..Default::default()
+                });
+
+                if table.runs.is_empty() {
+                    parent.spawn(TextComponents {
+                        style: Style {
+                            size: Size {
+                                height: Val::Px(30.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        text: Text {
+                            value: "no game played yet".to_string(),
+                            font: font_sub.clone(),
+                            style: TextStyle {
+                                color: crate::ui::ColorScheme::TEXT_DIM,
+                                font_size: 30.,
+                            },
+                        },
+                        ..Default::default()
+                    });
+                }
+
+                for (i, run) in table.runs.iter().enumerate() {
+                    let cause_of_death = run
+                        .cause_of_death
+                        .map(|cause| format!("death by {}", cause))
+                        .unwrap_or_default();
+                    let columns = [
+                        (format!("{}.", i + 1), 50.),
+                        (format!("{}", run.score), 150.),
+                        (format!("round {}", run.round), 150.),
+                    ];
+                    parent
+                        .spawn(NodeComponents {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                align_items: AlignItems::Center,
+                                margin: Rect {
+                                    bottom: Val::Px(10.),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            },
+                            material: color_none.clone(),
+                            ..Default::default()
+                        })
+                        .with_children(|row| {
+                            for (value, width) in columns.iter() {
+                                row.spawn(TextComponents {
+                                    style: Style {
+                                        size: Size {
+                                            width: Val::Px(*width),
+                                            height: Val::Px(30.),
+                                        },
+                                        ..Default::default()
+                                    },
+                                    text: Text {
+                                        value: value.clone(),
+                                        font: font_sub.clone(),
+                                        style: TextStyle {
+                                            color: crate::ui::ColorScheme::TEXT,
+                                            font_size: 30.,
+                                        },
+                                    },
+                                    ..Default::default()
+                                });
+                            }
+                            let medal_style = Style {
+                                size: Size {
+                                    width: Val::Px(30.),
+                                    height: Val::Px(30.),
+                                },
+                                margin: Rect {
+                                    right: Val::Px(30.),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            };
+                            if let Some(medal) = medals.for_score(run.score, &config) {
+                                row.spawn(ImageComponents {
+                                    style: medal_style,
+                                    material: medal,
+                                    ..Default::default()
+                                });
+                            } else {
+                                row.spawn(NodeComponents {
+                                    style: medal_style,
+                                    material: color_none.clone(),
+                                    ..Default::default()
+                                });
+                            }
+                            row.spawn(TextComponents {
+                                style: Style {
+                                    size: Size {
+                                        width: Val::Px(350.),
+                                        height: Val::Px(30.),
+                                    },
+                                    ..Default::default()
+                                },
+                                text: Text {
+                                    value: cause_of_death,
+                                    font: font_sub.clone(),
+                                    style: TextStyle {
+                                        color: crate::ui::ColorScheme::TEXT,
+                                        font_size: 30.,
+                                    },
+                                },
+                                ..Default::default()
+                            });
+                            row.spawn(TextComponents {
+                                style: Style {
+                                    size: Size {
+                                        height: Val::Px(30.),
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                },
+                                text: Text {
+                                    value: run.date.clone(),
+                                    font: font_sub.clone(),
+                                    style: TextStyle {
+                                        color: crate::ui::ColorScheme::TEXT_DIM,
+                                        font_size: 30.,
+                                    },
+                                },
+                                ..Default::default()
+                            });
+                        });
+                }
+            });
+
+        screen.loaded = true;
+        screen.reload = false;
+    }
+}
+
+fn tear_down(
+    mut commands: Commands,
+    game_screen: Res<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    query: Query<With<ScreenTag, Entity>>,
+) {
+    if (game_screen.current_screen != CURRENT_SCREEN || screen.reload) && screen.loaded {
+        info!("tear down");
+
+        for entity in &mut query.iter() {
+            commands.despawn_recursive(entity);
+        }
+
+        screen.loaded = false;
+    }
+}
+
+fn input_system(
+    mut game_screen: ResMut<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    keyboard_input: Res<Input<KeyCode>>,
+    bindings: Res<crate::bindings::Bindings>,
+    gamepad: Res<crate::gamepad::GamepadActions>,
+    mouse_button_input: Res<Input<MouseButton>>,
+) {
+    use crate::bindings::Action;
+    let triggered =
+        |action| bindings.just_released(&keyboard_input, action) || gamepad.just_pressed(action);
+    if game_screen.current_screen == CURRENT_SCREEN && screen.loaded {
+        if triggered(Action::Left) {
+            screen.difficulty = screen.difficulty.previous();
+            screen.reload = true;
+        } else if triggered(Action::Right) {
+            screen.difficulty = screen.difficulty.next();
+            screen.reload = true;
+        } else if mouse_button_input.just_pressed(MouseButton::Left)
+            || triggered(Action::Back)
+            || triggered(Action::Confirm)
+        {
+            game_screen.current_screen = crate::Screen::Menu;
+        }
+    }
+}
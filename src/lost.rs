@@ -1,6 +1,10 @@
 use bevy::prelude::*;
 use tracing::info;
 
+use simulation::GameStats;
+
+use crate::game::Mode;
+
 const CURRENT_SCREEN: crate::Screen = crate::Screen::Lost;
 
 struct ScreenTag;
@@ -14,16 +18,6 @@ impl Default for Screen {
     }
 }
 
-#[derive(Default)]
-pub struct GameStats {
-    last_seen_cause_of_death: Option<crate::game::CauseOfDeath>,
-    bomb_placed: u16,
-    obstacle_destroyed_by_player: u16,
-    obstacle_destroyed_by_lazer: u16,
-    bomb_chained: u16,
-    score_first_bomb: u32,
-}
-
 pub struct Plugin;
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut AppBuilder) {
@@ -40,39 +34,14 @@ impl bevy::app::Plugin for Plugin {
 fn update_stats(
     mut stats: ResMut<GameStats>,
     game: Res<crate::game::Game>,
-    (mut interesting_event_reader, interesting_events): (
-        Local<EventReader<crate::game::InterestingEvent>>,
-        ResMut<Events<crate::game::InterestingEvent>>,
-    ),
-    (mut game_event_reader, game_events): (
-        Local<EventReader<crate::game::GameEvents>>,
-        ResMut<Events<crate::game::GameEvents>>,
+    (mut event_reader, events): (
+        Local<EventReader<simulation::Event>>,
+        Res<Events<simulation::Event>>,
     ),
 ) {
-    for event in interesting_event_reader.iter(&interesting_events) {
-        match event {
-            crate::game::InterestingEvent::BombChainDetonated => stats.bomb_chained += 1,
-            crate::game::InterestingEvent::BombPlaced => {
-                stats.bomb_placed += 1;
-                if stats.score_first_bomb == 0 {
-                    stats.score_first_bomb = game.score;
-                }
-            }
-            crate::game::InterestingEvent::ObstacleDestroyedByLaser => {
-                stats.obstacle_destroyed_by_lazer += 1
-            }
-            crate::game::InterestingEvent::ObstacleDestroyedByPlayer => {
-                stats.obstacle_destroyed_by_player += 1
-            }
-        }
+    for event in event_reader.iter(&events) {
+        stats.record(event, &game.sim);
     }
-    game_event_reader
-        .iter(&game_events)
-        .filter_map(|event| match event {
-            crate::game::GameEvents::Lost(c) => Some(c),
-            _ => None,
-        })
-        .for_each(|cause| stats.last_seen_cause_of_death = Some(*cause));
 }
 
 fn setup(
@@ -85,6 +54,8 @@ fn setup(
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut asset_handles: ResMut<crate::AssetHandles>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut highscores: ResMut<crate::highscores::HighScores>,
+    bindings: Res<crate::bindings::Bindings>,
 ) {
     if game_screen.current_screen == CURRENT_SCREEN && !screen.loaded {
         info!("Loading screen");
@@ -96,129 +67,164 @@ fn setup(
 
         let font_sub: Handle<Font> = asset_handles.get_font_sub_handle(&asset_server);
 
-        commands
-            .spawn(SpriteSheetComponents {
-                texture_atlas: character_handle,
-                transform: Transform::from_translation(Vec3::new(-200., 0., 0.)),
-                sprite: TextureAtlasSprite {
-                    index: 0,
-                    ..Default::default()
-                },
-                ..Default::default()
-            })
-            .with(Timer::from_seconds(0.2, true))
-            .with(ScreenTag);
-
-        commands
-            .spawn(NodeComponents {
-                style: Style {
-                    position_type: PositionType::Absolute,
-                    position: Rect::<Val> {
-                        left: Val::Percent(50.),
-                        right: Val::Undefined,
-                        top: Val::Percent(25.),
-                        bottom: Val::Undefined,
+        if game.sim.mode() == Mode::Solo {
+            commands
+                .spawn(SpriteSheetComponents {
+                    texture_atlas: character_handle,
+                    transform: Transform::from_translation(Vec3::new(-200., 0., 0.)),
+                    sprite: TextureAtlasSprite {
+                        index: 0,
+                        ..Default::default()
                     },
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    flex_direction: FlexDirection::ColumnReverse,
                     ..Default::default()
-                },
-                material: materials.add(Color::NONE.into()),
-                ..Default::default()
-            })
-            .with(ScreenTag)
-            .with_children(|parent| {
-                parent.spawn(TextComponents {
+                })
+                .with(Timer::from_seconds(0.2, true))
+                .with(ScreenTag);
+        }
+
+        if game.sim.mode() != Mode::Solo {
+            multiplayer_results(
+                &mut commands,
+                &game.sim,
+                &stats,
+                font,
+                font_sub,
+                &mut materials,
+            );
+        } else {
+            let player_stats = stats.of(0);
+            commands
+                .spawn(NodeComponents {
                     style: Style {
-                        size: Size {
-                            height: Val::Px(75.),
-                            ..Default::default()
+                        position_type: PositionType::Absolute,
+                        position: Rect::<Val> {
+                            left: Val::Percent(50.),
+                            right: Val::Undefined,
+                            top: Val::Percent(25.),
+                            bottom: Val::Undefined,
                         },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        flex_direction: FlexDirection::ColumnReverse,
                         ..Default::default()
                     },
-                    text: Text {
-                        value: "You lost".to_string(),
-                        font,
-                        style: TextStyle {
-                            color: crate::ui::ColorScheme::TEXT,
-                            font_size: 75.,
-                        },
-                    },
+                    material: materials.add(Color::NONE.into()),
                     ..Default::default()
-                });
-                parent.spawn(TextComponents {
-                    style: Style {
-                        size: Size {
-                            height: Val::Px(100.),
+                })
+                .with(ScreenTag)
+                .with_children(|parent| {
+                    parent.spawn(TextComponents {
+                        style: Style {
+                            size: Size {
+                                height: Val::Px(75.),
+                                ..Default::default()
+                            },
                             ..Default::default()
                         },
-                        ..Default::default()
-                    },
-                    text: Text {
-                        value: format!("{} points", game.score),
-                        font: font_sub.clone(),
-                        style: TextStyle {
-                            color: if game_screen.is_new_highscore(game.score) {
-                                crate::ui::ColorScheme::TEXT_HIGHLIGHT
-                            } else {
-                                crate::ui::ColorScheme::TEXT
+                        text: Text {
+                            value: "You lost".to_string(),
+                            font,
+                            style: TextStyle {
+                                color: crate::ui::ColorScheme::TEXT,
+                                font_size: 75.,
                             },
-                            font_size: 100.0,
                         },
-                    },
-                    ..Default::default()
-                });
-                parent.spawn(TextComponents {
-                    style: Style {
-                        size: Size {
-                            height: Val::Px(50.),
+                        ..Default::default()
+                    });
+                    parent.spawn(TextComponents {
+                        style: Style {
+                            size: Size {
+                                height: Val::Px(100.),
+                                ..Default::default()
+                            },
                             ..Default::default()
                         },
-                        ..Default::default()
-                    },
-                    text: Text {
-                        value: format!("at round {}", game.round),
-                        font: font_sub.clone(),
-                        style: TextStyle {
-                            color: if game_screen.is_new_highround(game.round) {
-                                crate::ui::ColorScheme::TEXT_HIGHLIGHT
-                            } else {
-                                crate::ui::ColorScheme::TEXT
+                        text: Text {
+                            value: format!("{} points", game.sim.score()),
+                            font: font_sub.clone(),
+                            style: TextStyle {
+                                color: if game.sim.arena().is_none()
+                                    && game_screen.is_new_highscore(game.sim.score())
+                                {
+                                    crate::ui::ColorScheme::TEXT_HIGHLIGHT
+                                } else {
+                                    crate::ui::ColorScheme::TEXT
+                                },
+                                font_size: 100.0,
                             },
-                            font_size: 50.,
                         },
-                    },
-                    ..Default::default()
-                });
-                let medals = asset_handles.get_medal_handles(&asset_server, &mut materials);
-                if let Some(medal) = match game.score {
-                    x if x < crate::CONFIG.score_bronze_medal => None,
-                    x if x < crate::CONFIG.score_silver_medal => Some(medals.bronze),
-                    x if x < crate::CONFIG.score_gold_medal => Some(medals.silver),
-                    _ => Some(medals.gold),
-                } {
-                    parent.spawn(ImageComponents {
+                        ..Default::default()
+                    });
+                    parent.spawn(TextComponents {
                         style: Style {
                             size: Size {
                                 height: Val::Px(50.),
                                 ..Default::default()
                             },
-                            margin: Rect {
-                                top: Val::Px(30.),
-                                ..Default::default()
-                            },
                             ..Default::default()
                         },
-                        material: medal,
+                        text: Text {
+                            value: format!("at round {}", game.sim.round()),
+                            font: font_sub.clone(),
+                            style: TextStyle {
+                                color: if game.sim.arena().is_none()
+                                    && game_screen.is_new_highround(game.sim.round())
+                                {
+                                    crate::ui::ColorScheme::TEXT_HIGHLIGHT
+                                } else {
+                                    crate::ui::ColorScheme::TEXT
+                                },
+                                font_size: 50.,
+                            },
+                        },
                         ..Default::default()
                     });
-                }
-                if let Some(cause_of_death) = stats.last_seen_cause_of_death.as_ref() {
+                    let medals = asset_handles.get_medal_handles(&asset_server, &mut materials);
+                    if let Some(medal) = medals.for_score(game.sim.score(), game.sim.config()) {
+                        parent.spawn(ImageComponents {
+                            style: Style {
+                                size: Size {
+                                    height: Val::Px(50.),
+                                    ..Default::default()
+                                },
+                                margin: Rect {
+                                    top: Val::Px(30.),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            },
+                            material: medal,
+                            ..Default::default()
+                        });
+                    }
+                    if let Some(cause_of_death) = player_stats.cause_of_death {
+                        parent.spawn(TextComponents {
+                            style: Style {
+                                size: Size {
+                                    height: Val::Px(50.),
+                                    ..Default::default()
+                                },
+                                margin: Rect {
+                                    top: Val::Px(30.),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            },
+                            text: Text {
+                                value: format!("death by {}", cause_of_death),
+                                font: font_sub.clone(),
+                                style: TextStyle {
+                                    color: crate::ui::ColorScheme::TEXT,
+                                    font_size: 50.,
+                                },
+                            },
+                            ..Default::default()
+                        });
+                    }
                     parent.spawn(TextComponents {
                         style: Style {
                             size: Size {
-                                height: Val::Px(50.),
+                                height: Val::Px(30.),
                                 ..Default::default()
                             },
                             margin: Rect {
@@ -228,69 +234,111 @@ fn setup(
                             ..Default::default()
                         },
                         text: Text {
-                            value: format!("death by {}", cause_of_death),
+                            value: format!(
+                                "placed {} bombs, {} chain explosion",
+                                player_stats.bomb_placed, player_stats.bomb_chained
+                            ),
                             font: font_sub.clone(),
                             style: TextStyle {
                                 color: crate::ui::ColorScheme::TEXT,
-                                font_size: 50.,
+                                font_size: 30.,
                             },
                         },
                         ..Default::default()
                     });
-                }
-                parent.spawn(TextComponents {
-                    style: Style {
-                        size: Size {
-                            height: Val::Px(30.),
+                    parent.spawn(TextComponents {
+                        style: Style {
+                            size: Size {
+                                height: Val::Px(30.),
+                                ..Default::default()
+                            },
                             ..Default::default()
                         },
-                        margin: Rect {
-                            top: Val::Px(30.),
-                            ..Default::default()
+                        text: Text {
+                            value: format!(
+                                "destroyed {} obstacles by bombs, {} by the laser",
+                                player_stats.obstacle_destroyed,
+                                stats.obstacle_destroyed_by_laser
+                                    + stats.obstacle_destroyed_by_barrel
+                            ),
+                            font: font_sub.clone(),
+                            style: TextStyle {
+                                color: crate::ui::ColorScheme::TEXT,
+                                font_size: 30.,
+                            },
                         },
                         ..Default::default()
-                    },
-                    text: Text {
-                        value: format!(
-                            "placed {} bombs, {} chain explosion",
-                            stats.bomb_placed, stats.bomb_chained
-                        ),
-                        font: font_sub.clone(),
-                        style: TextStyle {
-                            color: crate::ui::ColorScheme::TEXT,
-                            font_size: 30.,
-                        },
-                    },
-                    ..Default::default()
-                });
-                parent.spawn(TextComponents {
-                    style: Style {
-                        size: Size {
-                            height: Val::Px(30.),
+                    });
+                    parent.spawn(TextComponents {
+                        style: Style {
+                            size: Size {
+                                height: Val::Px(20.),
+                                ..Default::default()
+                            },
+                            margin: Rect {
+                                top: Val::Px(30.),
+                                ..Default::default()
+                            },
                             ..Default::default()
                         },
+                        text: Text {
+                            value: format!("seed {}", game.sim.seed()),
+                            font: font_sub.clone(),
+                            style: TextStyle {
+                                color: crate::ui::ColorScheme::TEXT_DIM,
+                                font_size: 20.,
+                            },
+                        },
                         ..Default::default()
-                    },
-                    text: Text {
-                        value: format!(
-                            "destroyed {} obstacles by bombs, {} by the laser",
-                            stats.obstacle_destroyed_by_player, stats.obstacle_destroyed_by_lazer
-                        ),
-                        font: font_sub.clone(),
-                        style: TextStyle {
-                            color: crate::ui::ColorScheme::TEXT,
-                            font_size: 30.,
+                    });
+                    parent.spawn(TextComponents {
+                        style: Style {
+                            size: Size {
+                                height: Val::Px(20.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
                         },
-                    },
-                    ..Default::default()
+                        text: Text {
+                            value: format!(
+                                "press {} to see the high scores",
+                                crate::bindings::describe(
+                                    bindings.keys(crate::bindings::Action::HighScores)
+                                )
+                            ),
+                            font: font_sub.clone(),
+                            style: TextStyle {
+                                color: crate::ui::ColorScheme::TEXT_DIM,
+                                font_size: 20.,
+                            },
+                        },
+                        ..Default::default()
+                    });
                 });
-            });
-
-        if game.score > game_screen.highscore {
-            game_screen.highscore = game.score;
         }
-        if game.round > game_screen.highround {
-            game_screen.highround = game.round;
+
+        crate::game::save_replay(&game.sim);
+        // games on a custom arena, like the ones tested from the editor, can't be compared with
+        // the others, and replays were already counted when they were played
+        if game.sim.mode() == Mode::Solo && game.sim.arena().is_none() && !game.sim.is_replay() {
+            highscores.add(
+                game_screen.difficulty,
+                crate::highscores::Run {
+                    score: game.sim.score(),
+                    round: game.sim.round(),
+                    cause_of_death: game.sim.player().death,
+                    seed: game.sim.seed(),
+                    date: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+                },
+            );
+            highscores.save();
+
+            if game.sim.score() > game_screen.highscore {
+                game_screen.highscore = game.sim.score();
+            }
+            if game.sim.round() > game_screen.highround {
+                game_screen.highround = game.sim.round();
+            }
         }
         *game = crate::game::Game::default();
 
@@ -298,6 +346,148 @@ fn setup(
     }
 }
 
+fn multiplayer_results(
+    commands: &mut Commands,
+    sim: &simulation::Simulation,
+    stats: &GameStats,
+    font: Handle<Font>,
+    font_sub: Handle<Font>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    let color_none = materials.add(Color::NONE.into());
+    let title = match (sim.mode(), sim.winner()) {
+        (Mode::Coop, _) => format!("{} points together", sim.score()),
+        (_, Some(winner)) => format!("Player {} wins", winner + 1),
+        (_, None) => "Draw".to_string(),
+    };
+    let text = |value: String, font: Handle<Font>, size: f32, color: Color| TextComponents {
+        style: Style {
+            size: Size {
+                height: Val::Px(size),
+                ..Default::default()
+            },
+            margin: Rect {
+                bottom: Val::Px(size / 3.),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        text: Text {
+            value,
+            font,
+            style: TextStyle {
+                color,
+                font_size: size,
+            },
+        },
+        ..Default::default()
+    };
+
+    commands
+        .spawn(NodeComponents {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect::<Val> {
+                    left: Val::Percent(10.),
+                    right: Val::Undefined,
+                    top: Val::Percent(20.),
+                    bottom: Val::Undefined,
+                },
+                flex_direction: FlexDirection::ColumnReverse,
+                ..Default::default()
+            },
+            material: color_none.clone(),
+            ..Default::default()
+        })
+        .with(ScreenTag)
+        .with_children(|parent| {
+            parent.spawn(text(title, font, 75., crate::ui::ColorScheme::TEXT));
+            parent.spawn(text(
+                format!("at round {}", sim.round()),
+                font_sub.clone(),
+                50.,
+                crate::ui::ColorScheme::TEXT,
+            ));
+            parent
+                .spawn(NodeComponents {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        margin: Rect {
+                            top: Val::Px(30.),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    material: color_none.clone(),
+                    ..Default::default()
+                })
+                .with_children(|row| {
+                    for (i, player) in sim.players().iter().enumerate() {
+                        let player_stats = stats.of(i);
+                        let color = if sim.winner() == Some(i) {
+                            crate::ui::ColorScheme::TEXT_HIGHLIGHT
+                        } else {
+                            crate::ui::ColorScheme::TEXT
+                        };
+                        let points = if sim.mode() == Mode::Coop {
+                            format!("revived {} times", player_stats.revived)
+                        } else {
+                            format!("{} points", player.score)
+                        };
+                        let lines = vec![
+                            (format!("Player {}", i + 1), 50., color),
+                            (points, 50., crate::ui::ColorScheme::TEXT),
+                            (
+                                player_stats
+                                    .cause_of_death
+                                    .filter(|_| !player.is_alive())
+                                    .map(|cause| format!("death by {}", cause))
+                                    .unwrap_or_else(|| "survived".to_string()),
+                                30.,
+                                crate::ui::ColorScheme::TEXT,
+                            ),
+                            (
+                                format!(
+                                    "placed {} bombs, {} chain explosion",
+                                    player_stats.bomb_placed, player_stats.bomb_chained
+                                ),
+                                30.,
+                                crate::ui::ColorScheme::TEXT,
+                            ),
+                            (
+                                format!("destroyed {} obstacles", player_stats.obstacle_destroyed),
+                                30.,
+                                crate::ui::ColorScheme::TEXT,
+                            ),
+                        ];
+                        row.spawn(NodeComponents {
+                            style: Style {
+                                flex_direction: FlexDirection::ColumnReverse,
+                                margin: Rect {
+                                    right: Val::Px(80.),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            },
+                            material: color_none.clone(),
+                            ..Default::default()
+                        })
+                        .with_children(|column| {
+                            for (value, size, color) in lines {
+                                column.spawn(text(value, font_sub.clone(), size, color));
+                            }
+                        });
+                    }
+                });
+            parent.spawn(text(
+                format!("seed {}", sim.seed()),
+                font_sub.clone(),
+                20.,
+                crate::ui::ColorScheme::TEXT_DIM,
+            ));
+        });
+}
+
 fn tear_down(
     mut commands: Commands,
     game_screen: Res<crate::GameScreen>,
@@ -320,14 +510,21 @@ fn input_system(
     screen: Res<Screen>,
     mouse_button_input: Res<Input<MouseButton>>,
     keyboard_input: Res<Input<KeyCode>>,
+    bindings: Res<crate::bindings::Bindings>,
+    gamepad: Res<crate::gamepad::GamepadActions>,
 ) {
-    if game_screen.current_screen == CURRENT_SCREEN
-        && screen.loaded
-        && (mouse_button_input.just_pressed(MouseButton::Left)
-            || keyboard_input.just_released(KeyCode::Escape)
-            || keyboard_input.just_released(KeyCode::Space))
-    {
-        game_screen.current_screen = crate::Screen::Menu;
+    use crate::bindings::Action;
+    let triggered =
+        |action| bindings.just_released(&keyboard_input, action) || gamepad.just_pressed(action);
+    if game_screen.current_screen == CURRENT_SCREEN && screen.loaded {
+        if triggered(Action::HighScores) {
+            game_screen.current_screen = crate::Screen::HighScores;
+        } else if mouse_button_input.just_pressed(MouseButton::Left)
+            || triggered(Action::Back)
+            || triggered(Action::Confirm)
+        {
+            game_screen.current_screen = crate::Screen::Menu;
+        }
     }
 }
 
@@ -3,104 +3,162 @@
 
 use bevy::{app::AppExit, prelude::*, window::WindowMode};
 use serde::{Deserialize, Serialize};
+use simulation::cli::arg_value;
 
 mod assets;
 pub mod ui;
 use assets::AssetHandles;
 
 mod about;
+mod audio;
+mod bindings;
+mod config_reload;
+mod controls;
+mod editor;
 mod game;
+mod gamepad;
+mod highscores;
 mod lost;
 mod menu;
+mod settings;
 mod splash;
 
+const SETTINGS_FILE: &str = "settings.conf";
+
 #[derive(Serialize, Deserialize, Debug)]
-struct Settings {
+pub struct Settings {
     width: u32,
     height: u32,
     fullscreen: bool,
+    vsync: bool,
+    /// between 0 and 1, for all sounds
+    volume: f32,
+    /// between 0 and 1
+    music_volume: f32,
+    /// between 0 and 1
+    sfx_volume: f32,
+    seed: Option<u64>,
+    /// file of the arena games are played on, the usual empty board when not set
+    arena: Option<String>,
+    pub bindings: bindings::BindingsSettings,
+    gamepad: gamepad::GamepadSettings,
 }
 
 impl Default for Settings {
     fn default() -> Settings {
         Settings {
-            width: 2560,
-            height: 1600,
+            width: 1280,
+            height: 720,
             fullscreen: false,
+            vsync: true,
+            volume: 0.5,
+            music_volume: 0.6,
+            sfx_volume: 1.,
+            seed: None,
+            arena: None,
+            bindings: bindings::BindingsSettings::default(),
+            gamepad: gamepad::GamepadSettings::default(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct Config {
-    score_bronze_medal: u32,
-    score_silver_medal: u32,
-    score_gold_medal: u32,
-    player_nb_bombs: usize,
-    player_bomb_range: usize,
-    player_bomb_damage: usize,
-    player_bomb_speed: u64,
-    player_speed: u64,
-    player_powerup_chance: f64,
-    player_powerup_bomb_damage: usize,
-    player_powerup_bomb_range: usize,
-    player_powerup_bomb_count: usize,
-    player_powerup_bomb_speed: f64,
-    player_powerup_score: u32,
-    player_bomb_fire_timer: f32,
-    powerup_timer: f32,
-    laser_fire_timer: f32,
-    laser_fire_damage: usize,
-    laser_speed: u64,
-    laser_spawn_obstacles_delay: u16,
-    laser_nb_obstacles: usize,
-    laser_obstacle_strength: usize,
-    laser_powerup_speed: f64,
-    laser_powerup_obstacle_delay: f32,
-    laser_powerup_obstacle_strength: usize,
-    laser_powerup_nb_obstacles: usize,
-}
+impl Settings {
+    pub fn save(&self) {
+        if let Err(err) = config::write_to(SETTINGS_FILE, self) {
+            tracing::warn!("error saving settings: {}", err);
+        }
+    }
 
-impl Default for Config {
-    fn default() -> Config {
-        Config {
-            score_bronze_medal: 10000,
-            score_silver_medal: 20000,
-            score_gold_medal: 35000,
-            player_nb_bombs: 2,
-            player_bomb_range: 1,
-            player_bomb_damage: 2,
-            player_bomb_speed: 2000,
-            player_speed: 200,
-            player_powerup_chance: 0.2,
-            player_powerup_bomb_damage: 2,
-            player_powerup_bomb_range: 1,
-            player_powerup_bomb_count: 1,
-            player_powerup_bomb_speed: 0.9,
-            player_powerup_score: 200,
-            player_bomb_fire_timer: 0.25,
-            powerup_timer: 20.,
-            laser_fire_timer: 1.5,
-            laser_fire_damage: 1,
-            laser_speed: 1000,
-            laser_spawn_obstacles_delay: 10000,
-            laser_nb_obstacles: 5,
-            laser_obstacle_strength: 2,
-            laser_powerup_speed: 0.9,
-            laser_powerup_obstacle_delay: 0.8,
-            laser_powerup_obstacle_strength: 2,
-            laser_powerup_nb_obstacles: 2,
+    pub fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::Fullscreen { use_size: true }
+        } else {
+            WindowMode::Windowed
         }
     }
+
+    /// Change the window to match the settings.
+    pub fn apply(&self, window: &mut Window) {
+        if (window.width(), window.height()) != (self.width, self.height) {
+            window.set_resolution(self.width, self.height);
+        }
+        if window.mode() != self.window_mode() {
+            window.set_mode(self.window_mode());
+        }
+        if window.vsync() != self.vsync {
+            window.set_vsync(self.vsync);
+        }
+    }
+}
+
+const CONFIG_FILE: &str = "config.conf";
+
+/// Report an error that prevents the game from starting, then exit. The game has no console on
+/// Windows, so the error is also shown in a message box there.
+fn exit_with_error(error: impl std::fmt::Display) -> ! {
+    let message = error.to_string();
+    eprintln!("{}", message);
+    #[cfg(windows)]
+    show_message_box(&message);
+    std::process::exit(1);
 }
 
-use lazy_static::lazy_static;
-lazy_static! {
-    static ref CONFIG: Config = config::read_from("config.conf").unwrap();
+#[cfg(windows)]
+fn show_message_box(message: &str) {
+    use std::{ffi::c_void, iter::once, ptr::null_mut};
+
+    const MB_ICONERROR: u32 = 0x10;
+    #[link(name = "user32")]
+    extern "system" {
+        fn MessageBoxW(
+            window: *mut c_void,
+            text: *const u16,
+            caption: *const u16,
+            kind: u32,
+        ) -> i32;
+    }
+    let wide = |text: &str| text.encode_utf16().chain(once(0)).collect::<Vec<u16>>();
+    let (text, caption) = (wide(message), wide("kmanb"));
+    unsafe {
+        MessageBoxW(null_mut(), text.as_ptr(), caption.as_ptr(), MB_ICONERROR);
+    }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let settings: Settings = config::read_from("settings.conf")?;
+fn main() {
+    // report all problems in configuration files before opening the window
+    let (settings, game_config): (Settings, _) = match (
+        config::read_from(SETTINGS_FILE),
+        simulation::Config::load(CONFIG_FILE),
+    ) {
+        (Ok(settings), Ok(config)) => (settings, config),
+        (settings, config) => {
+            let errors = settings
+                .err()
+                .into_iter()
+                .chain(config.err())
+                .flat_map(|errors| errors.0)
+                .collect();
+            exit_with_error(config::Errors(errors));
+        }
+    };
+
+    let seed = match arg_value("--seed") {
+        Some(seed) => Some(
+            seed.parse()
+                .unwrap_or_else(|err| exit_with_error(format!("--seed {}: {}", seed, err))),
+        ),
+        None => settings.seed,
+    };
+    let replay = arg_value("--replay")
+        .map(simulation::Replay::load)
+        .transpose()
+        .unwrap_or_else(|err| exit_with_error(err));
+    let arena = arg_value("--arena")
+        .or_else(|| settings.arena.clone())
+        .map(simulation::Arena::load)
+        .transpose()
+        .unwrap_or_else(|err| exit_with_error(err));
+    let highscores = highscores::HighScores::load();
 
     let _subscriber = if cfg!(debug_assertions) {
         tracing_subscriber::fmt()
@@ -120,17 +178,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // resources
         .add_resource(WindowDescriptor {
             title: "kmanb".to_string(),
-            // width: settings.width,
-            // height: settings.height,
-            vsync: true,
-            resizable: false,
-            mode: if settings.fullscreen {
-                WindowMode::Fullscreen { use_size: true }
-            } else {
-                WindowMode::Windowed
-            },
+            width: settings.width,
+            height: settings.height,
+            vsync: settings.vsync,
+            resizable: true,
+            mode: settings.window_mode(),
             ..Default::default()
         })
+        .add_resource(game_config)
+        .add_resource(game::GameRng::new(seed))
+        .add_resource(bindings::Bindings::from_settings(&settings.bindings))
+        .add_resource(gamepad::GamepadActions::new(&settings.gamepad))
+        .add_resource(game::ReplayMode(replay))
+        .add_resource(game::ArenaMode(arena))
         .add_resource(settings)
         .add_resource(ClearColor(Color::rgb(0., 0., 0.01)));
 
@@ -159,20 +219,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // game management
         .add_startup_system(general_setup.system())
         .add_system(handle_state.system())
-        .add_resource(GameScreen::default())
+        .add_resource({
+            let mut game_screen = GameScreen::default();
+            game_screen.set_difficulty(simulation::Difficulty::default(), &highscores);
+            game_screen
+        })
+        .add_resource(highscores)
         .add_stage_after(bevy::app::stage::UPDATE, custom_stage::TEAR_DOWN)
         // ui
         .add_plugin(crate::ui::button::Plugin)
         .add_resource(AssetHandles::default())
+        .add_plugin(crate::config_reload::Plugin)
+        .add_plugin(crate::gamepad::Plugin)
+        .add_plugin(crate::audio::Plugin)
         // screens
         .add_plugin(crate::splash::Plugin)
         .add_plugin(crate::menu::Plugin)
         .add_plugin(crate::about::Plugin)
+        .add_plugin(crate::highscores::Plugin)
+        .add_plugin(crate::controls::Plugin)
+        .add_plugin(crate::settings::Plugin)
+        .add_plugin(crate::editor::Plugin)
         .add_plugin(crate::game::Plugin)
         .add_plugin(crate::lost::Plugin)
         .run();
-
-    Ok(())
 }
 
 pub mod custom_stage {
@@ -184,6 +254,10 @@ pub enum Screen {
     Splash,
     Menu,
     About,
+    HighScores,
+    Controls,
+    Settings,
+    Editor,
     Game,
     Exit,
     Lost,
@@ -192,6 +266,10 @@ pub enum Screen {
 #[derive(Debug)]
 pub struct GameScreen {
     pub current_screen: Screen,
+    pub difficulty: simulation::Difficulty,
+    pub mode: simulation::Mode,
+    /// the game is played by a bot, to show it from the menu
+    pub demo: bool,
     pub highscore: u32,
     pub highround: u16,
 }
@@ -200,6 +278,9 @@ impl Default for GameScreen {
     fn default() -> Self {
         GameScreen {
             current_screen: Screen::Splash,
+            difficulty: simulation::Difficulty::default(),
+            mode: simulation::Mode::Solo,
+            demo: false,
             highscore: 0,
             highround: 0,
         }
@@ -213,10 +294,25 @@ impl GameScreen {
     pub fn is_new_highround(&self, round: u16) -> bool {
         self.highround != 0 && round > self.highround
     }
+    pub fn set_difficulty(
+        &mut self,
+        difficulty: simulation::Difficulty,
+        highscores: &highscores::HighScores,
+    ) {
+        let table = highscores.table(difficulty);
+        self.difficulty = difficulty;
+        self.highscore = table.highscore;
+        self.highround = table.highround;
+    }
 }
 
+/// The camera showing sprites, zoomed in game to follow the window size
+pub struct WorldCamera;
+
 fn general_setup(mut commands: Commands) {
-    commands.spawn(Camera2dComponents::default());
+    commands
+        .spawn(Camera2dComponents::default())
+        .with(WorldCamera);
     commands.spawn(UiCameraComponents::default());
 }
 
@@ -5,14 +5,20 @@ use tracing::info;
 
 use bevy_easings::Ease;
 
+use crate::bindings::{Action, Bindings};
+
 const CURRENT_SCREEN: crate::Screen = crate::Screen::Menu;
 
+/// Seconds without any input on the menu before a demo game starts
+const DEMO_AFTER: f32 = 20.;
+
 struct ScreenTag;
 
 struct Screen {
     loaded: bool,
     first_load: bool,
     menu_selected: Option<i32>,
+    idle: f32,
 }
 impl Default for Screen {
     fn default() -> Self {
@@ -20,6 +26,7 @@ impl Default for Screen {
             loaded: false,
             first_load: true,
             menu_selected: None,
+            idle: 0.,
         }
     }
 }
@@ -34,6 +41,8 @@ impl bevy::app::Plugin for Plugin {
             .add_system(animate_sprite_system.system())
             .add_system(remove_emote.system())
             .add_system(display_menu_item_selector.system())
+            .add_system(display_difficulty.system())
+            .add_system(attract_mode.system())
             .add_system_to_stage(crate::custom_stage::TEAR_DOWN, tear_down.system());
     }
 }
@@ -41,14 +50,74 @@ impl bevy::app::Plugin for Plugin {
 #[derive(Clone, Copy)]
 enum MenuButton {
     NewGame,
+    Versus,
+    Coop,
+    Difficulty,
+    HighScores,
+    Controls,
+    Settings,
+    Editor,
     About,
     Quit,
 }
 
+const BUTTONS: [MenuButton; 10] = [
+    MenuButton::NewGame,
+    MenuButton::Versus,
+    MenuButton::Coop,
+    MenuButton::Difficulty,
+    MenuButton::HighScores,
+    MenuButton::Controls,
+    MenuButton::Settings,
+    MenuButton::Editor,
+    MenuButton::About,
+    MenuButton::Quit,
+];
+
+impl MenuButton {
+    fn activate(
+        self,
+        game_screen: &mut crate::GameScreen,
+        highscores: &crate::highscores::HighScores,
+    ) {
+        match self {
+            MenuButton::NewGame => {
+                game_screen.mode = simulation::Mode::Solo;
+                game_screen.current_screen = crate::Screen::Game;
+            }
+            MenuButton::Versus => {
+                game_screen.mode = simulation::Mode::Versus;
+                game_screen.current_screen = crate::Screen::Game;
+            }
+            MenuButton::Coop => {
+                game_screen.mode = simulation::Mode::Coop;
+                game_screen.current_screen = crate::Screen::Game;
+            }
+            MenuButton::Difficulty => {
+                let difficulty = game_screen.difficulty.next();
+                game_screen.set_difficulty(difficulty, highscores);
+            }
+            MenuButton::HighScores => game_screen.current_screen = crate::Screen::HighScores,
+            MenuButton::Controls => game_screen.current_screen = crate::Screen::Controls,
+            MenuButton::Settings => game_screen.current_screen = crate::Screen::Settings,
+            MenuButton::Editor => game_screen.current_screen = crate::Screen::Editor,
+            MenuButton::About => game_screen.current_screen = crate::Screen::About,
+            MenuButton::Quit => game_screen.current_screen = crate::Screen::Exit,
+        }
+    }
+}
+
 impl Into<String> for MenuButton {
     fn into(self) -> String {
         match self {
             MenuButton::NewGame => "New Game".to_string(),
+            MenuButton::Versus => "Versus".to_string(),
+            MenuButton::Coop => "Co-op".to_string(),
+            MenuButton::Difficulty => "Difficulty".to_string(),
+            MenuButton::HighScores => "High Scores".to_string(),
+            MenuButton::Controls => "Controls".to_string(),
+            MenuButton::Settings => "Settings".to_string(),
+            MenuButton::Editor => "Arena Editor".to_string(),
             MenuButton::About => "About".to_string(),
             MenuButton::Quit => "Quit".to_string(),
         }
@@ -172,21 +241,21 @@ fn setup(
             position: Rect::<Val> {
                 left: Val::Percent(53.),
                 right: Val::Undefined,
-                bottom: Val::Percent(15.),
+                bottom: Val::Percent(10.),
                 top: Val::Undefined,
             },
             margin: Rect::all(Val::Px(0.)),
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
-            size: Size::new(Val::Px(400.), Val::Px(300.)),
+            size: Size::new(Val::Px(450.), Val::Px(740.)),
             align_content: AlignContent::Stretch,
             flex_direction: FlexDirection::ColumnReverse,
             ..Default::default()
         };
 
         let button_shift_start = 15.;
-        let button_shift = 45.;
-        let buttons = &[MenuButton::NewGame, MenuButton::About, MenuButton::Quit]
+        let button_shift = 17.;
+        let buttons = BUTTONS
             .iter()
             .enumerate()
             .map(|(i, button_item)| {
@@ -261,6 +330,34 @@ fn setup(
             })
             .current_entity()
             .unwrap();
+        let difficulty = commands
+            .spawn(TextComponents {
+                style: Style {
+                    size: Size {
+                        height: Val::Px(25.),
+                        ..Default::default()
+                    },
+                    margin: Rect {
+                        bottom: Val::Px(10.),
+                        left: Val::Px(button_shift_start + 32.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text {
+                    value: format!("< {} >", game_screen.difficulty),
+                    font: font.clone(),
+                    style: TextStyle {
+                        color: crate::ui::ColorScheme::TEXT_DARK,
+                        font_size: 25.,
+                    },
+                },
+                ..Default::default()
+            })
+            .with(DifficultyText)
+            .current_entity()
+            .unwrap();
+        commands.push_children(inner_content, &[difficulty]);
         commands.push_children(inner_content, buttons.as_slice());
 
         commands
@@ -282,7 +379,7 @@ fn setup(
                     position: Rect::<Val> {
                         left: Val::Percent(120.),
                         right: Val::Undefined,
-                        bottom: Val::Percent(15.),
+                        bottom: Val::Percent(10.),
                         top: Val::Undefined,
                     },
                     ..panel_style
@@ -301,6 +398,7 @@ fn setup(
 
         screen.loaded = true;
         screen.first_load = false;
+        screen.idle = 0.;
     }
 }
 
@@ -324,48 +422,79 @@ fn keyboard_input_system(
     mut game_screen: ResMut<crate::GameScreen>,
     mut screen: ResMut<Screen>,
     keyboard_input: Res<Input<KeyCode>>,
+    bindings: Res<Bindings>,
+    gamepad: Res<crate::gamepad::GamepadActions>,
     mut wnds: ResMut<Windows>,
+    mut settings: ResMut<crate::Settings>,
+    highscores: Res<crate::highscores::HighScores>,
 ) {
+    let triggered =
+        |action| bindings.just_released(&keyboard_input, action) || gamepad.just_pressed(action);
     if game_screen.current_screen == CURRENT_SCREEN && screen.loaded {
-        if keyboard_input.just_released(KeyCode::Escape) {
+        if triggered(Action::Back) {
             game_screen.current_screen = crate::Screen::Exit;
-        } else if keyboard_input.just_released(KeyCode::F) {
-            let window = wnds.get_primary_mut().unwrap();
-            match window.mode() {
-                bevy::window::WindowMode::Windowed => {
-                    window.set_mode(bevy::window::WindowMode::BorderlessFullscreen)
-                }
-                _ => window.set_mode(bevy::window::WindowMode::Windowed),
-            }
-        } else if keyboard_input.just_released(KeyCode::Down) {
+        } else if triggered(Action::Fullscreen) {
+            settings.fullscreen = !settings.fullscreen;
+            settings.apply(wnds.get_primary_mut().unwrap());
+            settings.save();
+        } else if triggered(Action::Down) {
             screen.menu_selected = Some(
                 screen
                     .menu_selected
-                    .map(|i| i32::min(2, i + 1))
+                    .map(|i| i32::min(BUTTONS.len() as i32 - 1, i + 1))
                     .unwrap_or(0),
             );
-        } else if keyboard_input.just_released(KeyCode::Up) {
+        } else if triggered(Action::Up) {
             screen.menu_selected = Some(
                 screen
                     .menu_selected
                     .map(|i| i32::max(0, i - 1))
                     .unwrap_or(0),
             );
-        } else if keyboard_input.just_released(KeyCode::Space)
-            || keyboard_input.just_released(KeyCode::Return)
-        {
-            match screen.menu_selected {
-                Some(0) => game_screen.current_screen = crate::Screen::Game,
-                Some(1) => game_screen.current_screen = crate::Screen::About,
-                Some(2) => game_screen.current_screen = crate::Screen::Exit,
-                _ => (),
+        } else if triggered(Action::Left) {
+            let difficulty = game_screen.difficulty.previous();
+            game_screen.set_difficulty(difficulty, &highscores);
+        } else if triggered(Action::Right) {
+            let difficulty = game_screen.difficulty.next();
+            game_screen.set_difficulty(difficulty, &highscores);
+        } else if triggered(Action::Confirm) {
+            if let Some(button) = screen.menu_selected.and_then(|i| BUTTONS.get(i as usize)) {
+                button.activate(&mut game_screen, &highscores);
             }
         }
     }
 }
 
+fn attract_mode(
+    time: Res<Time>,
+    mut game_screen: ResMut<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    gamepad: Res<crate::gamepad::GamepadActions>,
+) {
+    if game_screen.current_screen != CURRENT_SCREEN || !screen.loaded {
+        return;
+    }
+    if keyboard_input.get_pressed().next().is_some()
+        || mouse_input.get_pressed().next().is_some()
+        || Action::ALL.iter().any(|action| gamepad.pressed(*action))
+    {
+        screen.idle = 0.;
+        return;
+    }
+    screen.idle += time.delta_seconds;
+    if screen.idle > DEMO_AFTER {
+        info!("starting demo");
+        game_screen.demo = true;
+        game_screen.mode = simulation::Mode::Solo;
+        game_screen.current_screen = crate::Screen::Game;
+    }
+}
+
 fn button_system(
     mut game_screen: ResMut<crate::GameScreen>,
+    highscores: Res<crate::highscores::HighScores>,
     mut interaction_query: Query<(
         &Button,
         Mutated<Interaction>,
@@ -374,11 +503,7 @@ fn button_system(
 ) {
     for (_button, interaction, button_id) in interaction_query.iter_mut() {
         match *interaction {
-            Interaction::Clicked => match button_id.0 {
-                MenuButton::Quit => game_screen.current_screen = crate::Screen::Exit,
-                MenuButton::About => game_screen.current_screen = crate::Screen::About,
-                MenuButton::NewGame => game_screen.current_screen = crate::Screen::Game,
-            },
+            Interaction::Clicked => button_id.0.activate(&mut game_screen, &highscores),
             Interaction::Hovered => (),
             Interaction::None => (),
         }
@@ -439,6 +564,20 @@ fn remove_emote(mut commands: Commands, time: Res<Time>, mut emote: Mut<Emote>,
 
 struct MenuItemSelector(usize);
 
+struct DifficultyText;
+
+fn display_difficulty(
+    game_screen: Res<crate::GameScreen>,
+    mut query: Query<With<DifficultyText, &mut Text>>,
+) {
+    for mut text in query.iter_mut() {
+        let value = format!("< {} >", game_screen.difficulty);
+        if text.value != value {
+            text.value = value;
+        }
+    }
+}
+
 fn display_menu_item_selector(
     screen: Res<Screen>,
     mut query: Query<(&MenuItemSelector, &mut Draw)>,
@@ -0,0 +1,446 @@
+use bevy::prelude::*;
+use tracing::info;
+
+use crate::bindings::{Action, Bindings};
+
+const CURRENT_SCREEN: crate::Screen = crate::Screen::Settings;
+
+const RESOLUTIONS: [(u32, u32); 8] = [
+    (1024, 768),
+    (1280, 720),
+    (1366, 768),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1080),
+    (2560, 1440),
+    (3840, 2160),
+];
+
+const VOLUME_STEP: f32 = 0.1;
+
+struct ScreenTag;
+
+struct Screen {
+    loaded: bool,
+    selected: usize,
+}
+impl Default for Screen {
+    fn default() -> Self {
+        Screen {
+            loaded: false,
+            selected: 0,
+        }
+    }
+}
+
+pub struct Plugin;
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_resource(Screen::default())
+            .add_system(input_system.system())
+            .add_system(setup.system())
+            .add_system(button_system.system())
+            .add_system(display_values.system())
+            .add_system(display_item_selector.system())
+            .add_system_to_stage(crate::custom_stage::TEAR_DOWN, tear_down.system());
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SettingsButton {
+    Resolution,
+    Fullscreen,
+    Vsync,
+    Volume,
+    MusicVolume,
+    SfxVolume,
+    Back,
+}
+
+const BUTTONS: [SettingsButton; 7] = [
+    SettingsButton::Resolution,
+    SettingsButton::Fullscreen,
+    SettingsButton::Vsync,
+    SettingsButton::Volume,
+    SettingsButton::MusicVolume,
+    SettingsButton::SfxVolume,
+    SettingsButton::Back,
+];
+
+impl Into<String> for SettingsButton {
+    fn into(self) -> String {
+        match self {
+            SettingsButton::Resolution => "Resolution".to_string(),
+            SettingsButton::Fullscreen => "Fullscreen".to_string(),
+            SettingsButton::Vsync => "Vsync".to_string(),
+            SettingsButton::Volume => "Volume".to_string(),
+            SettingsButton::MusicVolume => "Music".to_string(),
+            SettingsButton::SfxVolume => "Sounds".to_string(),
+            SettingsButton::Back => "Back".to_string(),
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn percent(volume: f32) -> String {
+    format!("< {}% >", (volume * 100.).round())
+}
+
+fn step_volume(volume: &mut f32, backward: bool) {
+    let step = if backward { -VOLUME_STEP } else { VOLUME_STEP };
+    *volume = (((*volume + step) * 10.).round() / 10.).max(0.).min(1.);
+}
+
+impl SettingsButton {
+    fn value(self, settings: &crate::Settings) -> String {
+        match self {
+            SettingsButton::Resolution => format!("< {}x{} >", settings.width, settings.height),
+            SettingsButton::Fullscreen => format!("< {} >", on_off(settings.fullscreen)),
+            SettingsButton::Vsync => format!("< {} >", on_off(settings.vsync)),
+            SettingsButton::Volume => percent(settings.volume),
+            SettingsButton::MusicVolume => percent(settings.music_volume),
+            SettingsButton::SfxVolume => percent(settings.sfx_volume),
+            SettingsButton::Back => "".to_string(),
+        }
+    }
+
+    /// Move the setting to its next value, or the previous one when going `backward`. Returns
+    /// `true` if the setting was changed.
+    fn change(self, settings: &mut crate::Settings, backward: bool) -> bool {
+        match self {
+            SettingsButton::Resolution => {
+                let current = (settings.width, settings.height);
+                let next = if backward {
+                    RESOLUTIONS
+                        .iter()
+                        .rev()
+                        .find(|resolution| **resolution < current)
+                        .or_else(|| RESOLUTIONS.last())
+                } else {
+                    RESOLUTIONS
+                        .iter()
+                        .find(|resolution| **resolution > current)
+                        .or_else(|| RESOLUTIONS.first())
+                };
+                let (width, height) = *next.unwrap();
+                settings.width = width;
+                settings.height = height;
+            }
+            SettingsButton::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            SettingsButton::Vsync => settings.vsync = !settings.vsync,
+            SettingsButton::Volume => step_volume(&mut settings.volume, backward),
+            SettingsButton::MusicVolume => step_volume(&mut settings.music_volume, backward),
+            SettingsButton::SfxVolume => step_volume(&mut settings.sfx_volume, backward),
+            SettingsButton::Back => return false,
+        }
+        true
+    }
+
+    fn activate(
+        self,
+        game_screen: &mut crate::GameScreen,
+        settings: &mut crate::Settings,
+        windows: &mut Windows,
+        backward: bool,
+    ) {
+        if self == SettingsButton::Back {
+            game_screen.current_screen = crate::Screen::Menu;
+        } else if self.change(settings, backward) {
+            info!("{}: {}", Into::<String>::into(self), self.value(settings));
+            if let Some(window) = windows.get_primary_mut() {
+                settings.apply(window);
+            }
+            settings.save();
+        }
+    }
+}
+
+struct SettingValue(SettingsButton);
+
+struct ItemSelector(usize);
+
+fn setup(
+    mut commands: Commands,
+    game_screen: Res<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    settings: Res<crate::Settings>,
+    asset_server: Res<AssetServer>,
+    mut asset_handles: ResMut<crate::AssetHandles>,
+    mut nine_patches: ResMut<Assets<bevy_ninepatch::NinePatchBuilder<()>>>,
+    mut buttons: ResMut<Assets<crate::ui::button::Button>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if game_screen.current_screen == CURRENT_SCREEN && !screen.loaded {
+        info!("Loading screen");
+
+        let color_none = materials.add(Color::NONE.into());
+
+        let font: Handle<Font> = asset_handles.get_font_main_handle(&asset_server);
+        let font_sub: Handle<Font> = asset_handles.get_font_sub_handle(&asset_server);
+
+        let button_handle = asset_handles.get_button_handle(
+            &asset_server,
+            &mut materials,
+            &mut nine_patches,
+            &mut buttons,
+        );
+        let button = buttons.get(&button_handle).unwrap();
+        let menu_indicator: Handle<ColorMaterial> =
+            asset_handles.get_ui_selection_handle(&asset_server, &mut materials);
+
+        let title = commands
+            .spawn(TextComponents {
+                style: Style {
+                    size: Size {
+                        height: Val::Px(150. / 2.),
+                        ..Default::default()
+                    },
+                    margin: Rect {
+                        bottom: Val::Px(30.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text {
+                    value: "Settings".to_string(),
+                    font: font.clone(),
+                    style: TextStyle {
+                        color: crate::ui::ColorScheme::TEXT,
+                        font_size: 150.0 / 2.,
+                    },
+                },
+                ..Default::default()
+            })
+            .current_entity()
+            .unwrap();
+        let hint = commands
+            .spawn(TextComponents {
+                style: Style {
+                    size: Size {
+                        height: Val::Px(20.),
+                        ..Default::default()
+                    },
+                    margin: Rect {
+                        bottom: Val::Px(30.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text {
+                    value: "up and down to select, left and right to change".to_string(),
+                    font: font_sub.clone(),
+                    style: TextStyle {
+                        color: crate::ui::ColorScheme::TEXT_DIM,
+                        font_size: 20.,
+                    },
+                },
+                ..Default::default()
+            })
+            .current_entity()
+            .unwrap();
+
+        let rows = BUTTONS
+            .iter()
+            .enumerate()
+            .map(|(i, button_item)| {
+                let row = commands
+                    .spawn(NodeComponents {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            margin: Rect {
+                                bottom: Val::Px(10.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        draw: Draw {
+                            is_transparent: true,
+                            ..Default::default()
+                        },
+                        material: color_none.clone(),
+                        ..Default::default()
+                    })
+                    .current_entity()
+                    .unwrap();
+                let indicator = commands
+                    .spawn(ImageComponents {
+                        style: Style {
+                            size: Size {
+                                height: Val::Px(17.),
+                                width: Val::Px(17.),
+                            },
+                            margin: Rect {
+                                right: Val::Px(15.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        draw: Draw {
+                            is_transparent: true,
+                            is_visible: false,
+                            ..Default::default()
+                        },
+                        material: menu_indicator.clone(),
+                        ..Default::default()
+                    })
+                    .with(ItemSelector(i))
+                    .current_entity()
+                    .unwrap();
+                let button = button.add(
+                    &mut commands,
+                    225.,
+                    50.,
+                    Rect::all(Val::Auto),
+                    font.clone(),
+                    *button_item,
+                    25.,
+                );
+                let value = commands
+                    .spawn(TextComponents {
+                        style: Style {
+                            size: Size {
+                                width: Val::Px(250.),
+                                height: Val::Px(25.),
+                            },
+                            margin: Rect {
+                                left: Val::Px(30.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        text: Text {
+                            value: button_item.value(&settings),
+                            font: font_sub.clone(),
+                            style: TextStyle {
+                                color: crate::ui::ColorScheme::TEXT,
+                                font_size: 25.,
+                            },
+                        },
+                        ..Default::default()
+                    })
+                    .with(SettingValue(*button_item))
+                    .current_entity()
+                    .unwrap();
+                commands.push_children(row, &[indicator, button, value]);
+                row
+            })
+            .collect::<Vec<_>>();
+
+        let content = commands
+            .spawn(NodeComponents {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect::<Val> {
+                        left: Val::Percent(20.),
+                        right: Val::Undefined,
+                        bottom: Val::Undefined,
+                        top: Val::Percent(10.),
+                    },
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..Default::default()
+                },
+                material: color_none,
+                ..Default::default()
+            })
+            .with(ScreenTag)
+            .current_entity()
+            .unwrap();
+        commands.push_children(content, &[title, hint]);
+        commands.push_children(content, rows.as_slice());
+
+        screen.loaded = true;
+    }
+}
+
+fn tear_down(
+    mut commands: Commands,
+    game_screen: Res<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    query: Query<With<ScreenTag, Entity>>,
+) {
+    if game_screen.current_screen != CURRENT_SCREEN && screen.loaded {
+        info!("tear down");
+
+        for entity in &mut query.iter() {
+            commands.despawn_recursive(entity);
+        }
+
+        screen.loaded = false;
+    }
+}
+
+fn input_system(
+    mut game_screen: ResMut<crate::GameScreen>,
+    mut screen: ResMut<Screen>,
+    keyboard_input: Res<Input<KeyCode>>,
+    bindings: Res<Bindings>,
+    gamepad: Res<crate::gamepad::GamepadActions>,
+    mut settings: ResMut<crate::Settings>,
+    mut wnds: ResMut<Windows>,
+) {
+    if game_screen.current_screen != CURRENT_SCREEN || !screen.loaded {
+        return;
+    }
+    let triggered =
+        |action| bindings.just_released(&keyboard_input, action) || gamepad.just_pressed(action);
+    let selected = BUTTONS[screen.selected];
+    if triggered(Action::Down) {
+        screen.selected = usize::min(BUTTONS.len() - 1, screen.selected + 1);
+    } else if triggered(Action::Up) {
+        screen.selected = screen.selected.saturating_sub(1);
+    } else if triggered(Action::Left) && selected != SettingsButton::Back {
+        selected.activate(&mut game_screen, &mut settings, &mut wnds, true);
+    } else if triggered(Action::Right) && selected != SettingsButton::Back {
+        selected.activate(&mut game_screen, &mut settings, &mut wnds, false);
+    } else if triggered(Action::Confirm) {
+        selected.activate(&mut game_screen, &mut settings, &mut wnds, false);
+    } else if triggered(Action::Back) {
+        game_screen.current_screen = crate::Screen::Menu;
+    }
+}
+
+fn button_system(
+    mut game_screen: ResMut<crate::GameScreen>,
+    mut settings: ResMut<crate::Settings>,
+    mut wnds: ResMut<Windows>,
+    mut interaction_query: Query<(
+        &Button,
+        Mutated<Interaction>,
+        &crate::ui::button::ButtonId<SettingsButton>,
+    )>,
+) {
+    for (_button, interaction, button_id) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                button_id
+                    .0
+                    .activate(&mut game_screen, &mut settings, &mut wnds, false)
+            }
+            Interaction::Hovered => (),
+            Interaction::None => (),
+        }
+    }
+}
+
+fn display_values(settings: Res<crate::Settings>, mut query: Query<(&SettingValue, &mut Text)>) {
+    for (setting, mut text) in query.iter_mut() {
+        let value = setting.0.value(&settings);
+        if text.value != value {
+            text.value = value;
+        }
+    }
+}
+
+fn display_item_selector(screen: Res<Screen>, mut query: Query<(&ItemSelector, &mut Draw)>) {
+    for (selector, mut draw) in query.iter_mut() {
+        draw.is_visible = selector.0 == screen.selected;
+    }
+}